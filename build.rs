@@ -0,0 +1,26 @@
+use std::env;
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+// Stable Rust can't pick a type from a const generic, so each length that
+// `Array` supports there gets its `Array::Index` width written out as an impl:
+// every length below 0x1000, and the powers of two up to 0x1000000.
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+
+    let mut impls = String::new();
+    for len in (0..0x1000).chain((12..=24).map(|shift| 1usize << shift)) {
+        let index = if len <= 0xff {
+            "u8"
+        } else if len <= 0xffff {
+            "u16"
+        } else {
+            "u32"
+        };
+        writeln!(impls, "impl IndexLen for [(); {:#x}] {{ type Index = {}; }}", len, index).unwrap();
+    }
+
+    let out = Path::new(&env::var_os("OUT_DIR").unwrap()).join("index_len.rs");
+    fs::write(out, impls).unwrap();
+}
@@ -1,8 +1,14 @@
-use std::ops::{Index, IndexMut, Deref, DerefMut, Range, RangeFrom, RangeTo, RangeFull};
-use std::mem::{MaybeUninit, transmute};
-use std::hash::Hash;
-use std::fmt::Debug;
+use core::ops::{Index, IndexMut, Deref, DerefMut, Range, RangeFrom, RangeTo, RangeFull};
+use core::mem::MaybeUninit;
+use core::hash::Hash;
+use core::fmt::Debug;
 
+/// A fixed-size array type usable as backing storage.
+///
+/// # Safety
+///
+/// `as_ptr`, `as_mut_ptr` and the uninit accessors must address `len()`
+/// contiguous items of `Self::Item`.
 pub unsafe trait Array: Sized {
     type Item;
     type Index: ArrayIndex;
@@ -35,6 +41,11 @@ impl ArrayIndex for u32 {
     #[inline] fn from_usize(u: usize) -> Self { u as Self }
 }
 
+impl ArrayIndex for usize {
+    #[inline] fn to_usize(self) -> usize { self }
+    #[inline] fn from_usize(u: usize) -> Self { u }
+}
+
 pub trait Addressable<T>:
     Index<usize, Output=T> +
     IndexMut<usize> +
@@ -69,38 +80,70 @@ impl<T, U> Addressable<U> for T where T:
 {
 }
 
-macro_rules! array_impl {
-    ($i:ty => $t:expr) => {
-        unsafe impl<T> Array for [T; $t] {
-            type Item = T;
-            type Index = $i;
-
-            fn len() -> usize { $t }
-            fn as_ptr(&self) -> *const Self::Item { self[..].as_ptr() }
-            fn as_mut_ptr(&mut self) -> *mut Self::Item { self[..].as_mut_ptr() }
-            fn as_uninit(this: &MaybeUninit<Self>) -> &[MaybeUninit<Self::Item>] { unsafe { &transmute::<_, &[MaybeUninit<T>; $t]>(this)[..] } }
-            fn as_uninit_mut(this: &mut MaybeUninit<Self>) -> &mut [MaybeUninit<Self::Item>] { unsafe { &mut transmute::<_, &mut [MaybeUninit<T>; $t]>(this)[..] } }
-        }
-    };
-    ($i:ty => $($t:expr),+) => {
-        $(
-            array_impl!($i => $t);
-        )+
-    };
+/// Maps an array length to the smallest `ArrayIndex` that can hold it.
+///
+/// Stable Rust can't compute a type from `N`, so the build script implements
+/// this for every length below `0x1000` and the powers of two up to
+/// `0x1000000`. The `unstable` feature lifts that limit.
+#[cfg(not(feature = "unstable"))]
+pub trait IndexLen {
+    type Index: ArrayIndex;
+}
+
+#[cfg(not(feature = "unstable"))]
+include!(concat!(env!("OUT_DIR"), "/index_len.rs"));
+
+#[cfg(not(feature = "unstable"))]
+unsafe impl<T, const N: usize> Array for [T; N] where [(); N]: IndexLen {
+    type Item = T;
+    type Index = <[(); N] as IndexLen>::Index;
+
+    #[inline] fn len() -> usize { N }
+    #[inline] fn as_ptr(&self) -> *const Self::Item { self[..].as_ptr() }
+    #[inline] fn as_mut_ptr(&mut self) -> *mut Self::Item { self[..].as_mut_ptr() }
+    #[inline] fn as_uninit(this: &MaybeUninit<Self>) -> &[MaybeUninit<Self::Item>] { unsafe { &*(this as *const MaybeUninit<Self> as *const [MaybeUninit<T>; N]) } }
+    #[inline] fn as_uninit_mut(this: &mut MaybeUninit<Self>) -> &mut [MaybeUninit<Self::Item>] { unsafe { &mut *(this as *mut MaybeUninit<Self> as *mut [MaybeUninit<T>; N]) } }
+}
+
+/// Selects the smallest `ArrayIndex` that can hold lengths up to `N`.
+#[cfg(feature = "unstable")]
+pub struct IndexFor<const N: usize>;
+
+#[cfg(feature = "unstable")]
+pub const fn index_width(len: usize) -> usize {
+    if len <= u8::MAX as usize {
+        1
+    } else if len <= u16::MAX as usize {
+        2
+    } else if len as u64 <= u32::MAX as u64 {
+        4
+    } else {
+        8
+    }
+}
+
+#[cfg(feature = "unstable")]
+pub trait SelectIndex {
+    type Index: ArrayIndex;
 }
 
-array_impl!(u8 =>
-    0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16,
-    17, 18, 19, 20, 21, 22, 23, 24, 25, 26, 27, 28, 29, 30, 31, 32,
-    0x40, 0x80
-);
-
-array_impl!(u16 =>
-    0x100, 0x200, 0x400, 0x800, 0x1000, 0x2000, 0x4000, 0x8000
-);
-
-array_impl!(u32 =>
-    0x10000, 0x20000, 0x40000, 0x80000,
-    0x100000, 0x200000, 0x400000, 0x800000,
-    0x1000000
-);
+#[cfg(feature = "unstable")]
+impl SelectIndex for IndexFor<1> { type Index = u8; }
+#[cfg(feature = "unstable")]
+impl SelectIndex for IndexFor<2> { type Index = u16; }
+#[cfg(feature = "unstable")]
+impl SelectIndex for IndexFor<4> { type Index = u32; }
+#[cfg(feature = "unstable")]
+impl SelectIndex for IndexFor<8> { type Index = usize; }
+
+#[cfg(feature = "unstable")]
+unsafe impl<T, const N: usize> Array for [T; N] where IndexFor<{ index_width(N) }>: SelectIndex {
+    type Item = T;
+    type Index = <IndexFor<{ index_width(N) }> as SelectIndex>::Index;
+
+    #[inline] fn len() -> usize { N }
+    #[inline] fn as_ptr(&self) -> *const Self::Item { self[..].as_ptr() }
+    #[inline] fn as_mut_ptr(&mut self) -> *mut Self::Item { self[..].as_mut_ptr() }
+    #[inline] fn as_uninit(this: &MaybeUninit<Self>) -> &[MaybeUninit<Self::Item>] { unsafe { &*(this as *const MaybeUninit<Self> as *const [MaybeUninit<T>; N]) } }
+    #[inline] fn as_uninit_mut(this: &mut MaybeUninit<Self>) -> &mut [MaybeUninit<Self::Item>] { unsafe { &mut *(this as *mut MaybeUninit<Self> as *mut [MaybeUninit<T>; N]) } }
+}
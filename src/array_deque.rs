@@ -0,0 +1,543 @@
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::mem::{MaybeUninit, replace};
+use core::ptr::{NonNull, read, write, copy, drop_in_place, slice_from_raw_parts_mut};
+use core::ops::{Index, IndexMut, Range, RangeBounds};
+use core::marker::PhantomData;
+use core::slice::{self, from_raw_parts, from_raw_parts_mut};
+use core::iter::{FromIterator, FusedIterator};
+use crate::array::{Array, ArrayIndex};
+use crate::error::CapacityError;
+use crate::util::{PointerExt, range_bounds};
+
+pub struct ArrayDeque<A: Array> {
+    array: MaybeUninit<A>,
+    head: A::Index,
+    len: A::Index,
+}
+
+impl<A: Array> ArrayDeque<A> {
+    #[inline]
+    pub fn new() -> Self {
+        ArrayDeque {
+            array: MaybeUninit::uninit(),
+            head: Default::default(),
+            len: Default::default(),
+        }
+    }
+
+    #[inline]
+    pub fn capacity(&self) -> usize { A::len() }
+
+    #[inline]
+    pub fn len(&self) -> usize { ArrayIndex::to_usize(self.len) }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool { self.len() == 0 }
+
+    #[inline]
+    pub fn is_full(&self) -> bool { self.len() == A::len() }
+
+    #[inline]
+    fn head(&self) -> usize { ArrayIndex::to_usize(self.head) }
+
+    #[inline]
+    fn set_head(&mut self, head: usize) { self.head = ArrayIndex::from_usize(head) }
+
+    #[inline]
+    unsafe fn set_len(&mut self, len: usize) { self.len = ArrayIndex::from_usize(len) }
+
+    #[inline]
+    fn ptr(&self) -> *const A::Item {
+        Array::as_uninit(&self.array).as_ptr() as *const _
+    }
+
+    #[inline]
+    fn mut_ptr(&mut self) -> *mut A::Item {
+        Array::as_uninit_mut(&mut self.array).as_mut_ptr() as *mut _
+    }
+
+    // Maps a logical index onto the backing array.
+    #[inline]
+    fn wrap(&self, index: usize) -> usize {
+        let index = self.head() + index;
+        if index >= A::len() {
+            index - A::len()
+        } else {
+            index
+        }
+    }
+
+    pub fn try_push_back(&mut self, value: A::Item) -> Result<(), CapacityError<A::Item>> {
+        if self.is_full() {
+            return Err(CapacityError::new(value))
+        }
+
+        let len = self.len();
+        unsafe {
+            let index = self.wrap(len);
+            write(self.mut_ptr().uoffset(index), value);
+            self.set_len(len + 1);
+        }
+        Ok(())
+    }
+
+    pub fn try_push_front(&mut self, value: A::Item) -> Result<(), CapacityError<A::Item>> {
+        if self.is_full() {
+            return Err(CapacityError::new(value))
+        }
+
+        let len = self.len();
+        let head = if self.head() == 0 { A::len() - 1 } else { self.head() - 1 };
+        unsafe {
+            write(self.mut_ptr().uoffset(head), value);
+            self.set_head(head);
+            self.set_len(len + 1);
+        }
+        Ok(())
+    }
+
+    #[inline]
+    pub fn push_back(&mut self, value: A::Item) {
+        if self.try_push_back(value).is_err() {
+            panic!("ArrayDeque capacity exceeded")
+        }
+    }
+
+    #[inline]
+    pub fn push_front(&mut self, value: A::Item) {
+        if self.try_push_front(value).is_err() {
+            panic!("ArrayDeque capacity exceeded")
+        }
+    }
+
+    pub fn push_back_overwrite(&mut self, value: A::Item) -> Option<A::Item> {
+        match self.try_push_back(value) {
+            Ok(()) => None,
+            Err(e) if A::len() == 0 => Some(e.element()),
+            Err(e) => {
+                let front = self.pop_front();
+                self.push_back(e.element());
+                front
+            },
+        }
+    }
+
+    pub fn push_front_overwrite(&mut self, value: A::Item) -> Option<A::Item> {
+        match self.try_push_front(value) {
+            Ok(()) => None,
+            Err(e) if A::len() == 0 => Some(e.element()),
+            Err(e) => {
+                let back = self.pop_back();
+                self.push_front(e.element());
+                back
+            },
+        }
+    }
+
+    pub fn pop_front(&mut self) -> Option<A::Item> {
+        let len = self.len();
+        if len == 0 {
+            return None
+        }
+
+        unsafe {
+            let v = read(self.ptr().uoffset(self.head()));
+            let head = self.wrap(1);
+            self.set_head(head);
+            self.set_len(len - 1);
+            Some(v)
+        }
+    }
+
+    pub fn pop_back(&mut self) -> Option<A::Item> {
+        let len = self.len();
+        if len == 0 {
+            return None
+        }
+
+        unsafe {
+            self.set_len(len - 1);
+            Some(read(self.ptr().uoffset(self.wrap(len - 1))))
+        }
+    }
+
+    #[inline]
+    pub fn get(&self, index: usize) -> Option<&A::Item> {
+        if index < self.len() {
+            unsafe { Some(&*self.ptr().uoffset(self.wrap(index))) }
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut A::Item> {
+        if index < self.len() {
+            let index = self.wrap(index);
+            unsafe { Some(&mut *self.mut_ptr().uoffset(index)) }
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    pub fn front(&self) -> Option<&A::Item> { self.get(0) }
+
+    #[inline]
+    pub fn front_mut(&mut self) -> Option<&mut A::Item> { self.get_mut(0) }
+
+    #[inline]
+    pub fn back(&self) -> Option<&A::Item> { self.len().checked_sub(1).and_then(|i| self.get(i)) }
+
+    #[inline]
+    pub fn back_mut(&mut self) -> Option<&mut A::Item> { self.len().checked_sub(1).and_then(move |i| self.get_mut(i)) }
+
+    // Splits the occupied region into its two physical runs.
+    fn split(&self) -> (usize, usize, usize) {
+        let (head, len) = (self.head(), self.len());
+        if head + len <= A::len() {
+            (head, len, 0)
+        } else {
+            (head, A::len() - head, head + len - A::len())
+        }
+    }
+
+    pub fn as_slices(&self) -> (&[A::Item], &[A::Item]) {
+        let (head, a, b) = self.split();
+        unsafe {
+            (from_raw_parts(self.ptr().uoffset(head), a), from_raw_parts(self.ptr(), b))
+        }
+    }
+
+    pub fn as_mut_slices(&mut self) -> (&mut [A::Item], &mut [A::Item]) {
+        let (head, a, b) = self.split();
+        let ptr = self.mut_ptr();
+        unsafe {
+            (from_raw_parts_mut(ptr.uoffset(head), a), from_raw_parts_mut(ptr, b))
+        }
+    }
+
+    pub fn make_contiguous(&mut self) -> &mut [A::Item] {
+        let head = self.head();
+        if head + self.len() > A::len() {
+            Array::as_uninit_mut(&mut self.array).rotate_left(head);
+            self.set_head(0);
+        }
+        self.as_mut_slices().0
+    }
+
+    pub fn clear(&mut self) {
+        let (head, len) = (self.head(), self.len());
+        let first = len.min(A::len() - head);
+        unsafe {
+            self.set_len(0);
+            self.set_head(0);
+            let ptr = self.mut_ptr();
+            drop_in_place(slice_from_raw_parts_mut(ptr.uoffset(head), first));
+            drop_in_place(slice_from_raw_parts_mut(ptr, len - first));
+        }
+    }
+
+    #[inline]
+    pub fn iter(&self) -> ArrayDequeIter<'_, A::Item> {
+        let (a, b) = self.as_slices();
+        ArrayDequeIter(a.iter(), b.iter())
+    }
+
+    #[inline]
+    pub fn iter_mut(&mut self) -> ArrayDequeIterMut<'_, A::Item> {
+        let (a, b) = self.as_mut_slices();
+        ArrayDequeIterMut(a.iter_mut(), b.iter_mut())
+    }
+
+    pub fn drain<R: RangeBounds<usize>>(&mut self, range: R) -> ArrayDequeDrain<'_, A> {
+        let len = self.len();
+        let (start, end) = range_bounds(range, len);
+        self.make_contiguous();
+
+        unsafe {
+            self.set_len(start);
+            let head = self.head();
+            let mut deque = NonNull::from(self);
+            let base = deque.as_mut().mut_ptr().uoffset(head);
+
+            ArrayDequeDrain {
+                deque,
+                base,
+                remaining: start..end,
+                tail_start: end,
+                tail_len: len - end,
+                _phantom: PhantomData,
+            }
+        }
+    }
+}
+
+impl<A: Array> Drop for ArrayDeque<A> {
+    fn drop(&mut self) {
+        self.clear()
+    }
+}
+
+impl<A: Array> Default for ArrayDeque<A> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<A: Array> Clone for ArrayDeque<A> where A::Item: Clone {
+    fn clone(&self) -> Self {
+        self.iter().cloned().collect()
+    }
+}
+
+impl<A: Array> fmt::Debug for ArrayDeque<A> where A::Item: fmt::Debug {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<A: Array> Hash for ArrayDeque<A> where A::Item: Hash {
+    fn hash<H: Hasher>(&self, h: &mut H) {
+        self.len().hash(h);
+        for v in self {
+            v.hash(h);
+        }
+    }
+}
+
+impl<A: Array, B: Array> PartialEq<ArrayDeque<B>> for ArrayDeque<A> where A::Item: PartialEq<B::Item> {
+    fn eq(&self, other: &ArrayDeque<B>) -> bool {
+        self.len() == other.len() && self.iter().zip(other).all(|(a, b)| a == b)
+    }
+}
+
+impl<A: Array> Eq for ArrayDeque<A> where A::Item: Eq { }
+
+impl<A: Array> Index<usize> for ArrayDeque<A> {
+    type Output = A::Item;
+
+    #[inline]
+    fn index(&self, index: usize) -> &A::Item {
+        self.get(index).expect("ArrayDeque index out of bounds")
+    }
+}
+
+impl<A: Array> IndexMut<usize> for ArrayDeque<A> {
+    #[inline]
+    fn index_mut(&mut self, index: usize) -> &mut A::Item {
+        self.get_mut(index).expect("ArrayDeque index out of bounds")
+    }
+}
+
+impl<A: Array> Extend<A::Item> for ArrayDeque<A> {
+    fn extend<I: IntoIterator<Item=A::Item>>(&mut self, iter: I) {
+        for v in iter {
+            self.push_back(v);
+        }
+    }
+}
+
+impl<A: Array> FromIterator<A::Item> for ArrayDeque<A> {
+    fn from_iter<I: IntoIterator<Item=A::Item>>(iter: I) -> Self {
+        let mut s = Self::new();
+        s.extend(iter);
+        s
+    }
+}
+
+impl<A: Array> From<A> for ArrayDeque<A> {
+    fn from(array: A) -> Self {
+        ArrayDeque {
+            array: MaybeUninit::new(array),
+            head: Default::default(),
+            len: ArrayIndex::from_usize(A::len()),
+        }
+    }
+}
+
+pub struct ArrayDequeIter<'a, T>(slice::Iter<'a, T>, slice::Iter<'a, T>);
+
+impl<'a, T> Iterator for ArrayDequeIter<'a, T> {
+    type Item = &'a T;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().or_else(|| self.1.next())
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.0.len() + self.1.len();
+        (len, Some(len))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for ArrayDequeIter<'a, T> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.1.next_back().or_else(|| self.0.next_back())
+    }
+}
+
+impl<'a, T> ExactSizeIterator for ArrayDequeIter<'a, T> { }
+
+impl<'a, T> FusedIterator for ArrayDequeIter<'a, T> { }
+
+pub struct ArrayDequeIterMut<'a, T>(slice::IterMut<'a, T>, slice::IterMut<'a, T>);
+
+impl<'a, T> Iterator for ArrayDequeIterMut<'a, T> {
+    type Item = &'a mut T;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().or_else(|| self.1.next())
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.0.len() + self.1.len();
+        (len, Some(len))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for ArrayDequeIterMut<'a, T> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.1.next_back().or_else(|| self.0.next_back())
+    }
+}
+
+impl<'a, T> ExactSizeIterator for ArrayDequeIterMut<'a, T> { }
+
+impl<'a, T> FusedIterator for ArrayDequeIterMut<'a, T> { }
+
+pub struct ArrayDequeIntoIter<A: Array>(ArrayDeque<A>);
+
+impl<A: Array> Iterator for ArrayDequeIntoIter<A> {
+    type Item = A::Item;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.pop_front()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.0.len();
+        (len, Some(len))
+    }
+}
+
+impl<A: Array> DoubleEndedIterator for ArrayDequeIntoIter<A> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.0.pop_back()
+    }
+}
+
+impl<A: Array> ExactSizeIterator for ArrayDequeIntoIter<A> { }
+
+impl<A: Array> FusedIterator for ArrayDequeIntoIter<A> { }
+
+impl<A: Array> IntoIterator for ArrayDeque<A> {
+    type Item = A::Item;
+    type IntoIter = ArrayDequeIntoIter<A>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        ArrayDequeIntoIter(self)
+    }
+}
+
+impl<'a, A: Array> IntoIterator for &'a ArrayDeque<A> {
+    type Item = &'a A::Item;
+    type IntoIter = ArrayDequeIter<'a, A::Item>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, A: Array> IntoIterator for &'a mut ArrayDeque<A> {
+    type Item = &'a mut A::Item;
+    type IntoIter = ArrayDequeIterMut<'a, A::Item>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+// The deque is made contiguous and its length cut to the start of the range
+// for the lifetime of the drain, as with `Vector::drain`.
+// Like `Drain`, the items are read through a raw base pointer rather than a
+// slice so that dropping them can't conflict with the deque's own borrows.
+pub struct ArrayDequeDrain<'a, A: Array + 'a> {
+    deque: NonNull<ArrayDeque<A>>,
+    base: *mut A::Item,
+    remaining: Range<usize>,
+    tail_start: usize,
+    tail_len: usize,
+    _phantom: PhantomData<&'a mut ArrayDeque<A>>,
+}
+
+unsafe impl<'a, A: Array + 'a> Send for ArrayDequeDrain<'a, A> where A::Item: Send { }
+
+unsafe impl<'a, A: Array + 'a> Sync for ArrayDequeDrain<'a, A> where A::Item: Sync { }
+
+impl<'a, A: Array + 'a> Iterator for ArrayDequeDrain<'a, A> {
+    type Item = A::Item;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.remaining.next().map(|i| unsafe { read(self.base.uoffset(i)) })
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.remaining.size_hint()
+    }
+}
+
+impl<'a, A: Array + 'a> DoubleEndedIterator for ArrayDequeDrain<'a, A> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.remaining.next_back().map(|i| unsafe { read(self.base.uoffset(i)) })
+    }
+}
+
+impl<'a, A: Array + 'a> ExactSizeIterator for ArrayDequeDrain<'a, A> { }
+
+impl<'a, A: Array + 'a> FusedIterator for ArrayDequeDrain<'a, A> { }
+
+impl<'a, A: Array + 'a> Drop for ArrayDequeDrain<'a, A> {
+    fn drop(&mut self) {
+        struct TailGuard<'r, 'a, A: Array + 'a>(&'r mut ArrayDequeDrain<'a, A>);
+
+        impl<'r, 'a, A: Array + 'a> Drop for TailGuard<'r, 'a, A> {
+            fn drop(&mut self) {
+                let drain = &mut *self.0;
+                unsafe {
+                    let deque = drain.deque.as_mut();
+                    let start = deque.len();
+                    let head = deque.head();
+                    if drain.tail_len > 0 && drain.tail_start != start {
+                        let ptr = deque.mut_ptr().uoffset(head);
+                        copy(ptr.uoffset(drain.tail_start), ptr.uoffset(start), drain.tail_len);
+                    }
+                    deque.set_len(start + drain.tail_len);
+                }
+            }
+        }
+
+        let remaining = replace(&mut self.remaining, 0..0);
+        let base = self.base;
+        let _guard = TailGuard(self);
+        unsafe {
+            drop_in_place(slice_from_raw_parts_mut(base.uoffset(remaining.start), remaining.len()));
+        }
+    }
+}
@@ -0,0 +1,242 @@
+use core::fmt;
+use core::str::{self, FromStr};
+use core::hash::{Hash, Hasher};
+use core::borrow::{Borrow, BorrowMut};
+use core::cmp::Ordering;
+use core::ops::{Deref, DerefMut};
+use crate::array_vec::ArrayVec;
+use crate::array::Array;
+use crate::error::CapacityError;
+use crate::vector::Vector;
+
+pub struct ArrayString<A: Array<Item=u8>> {
+    vec: ArrayVec<A>,
+}
+
+impl<A: Array<Item=u8>> ArrayString<A> {
+    #[inline]
+    pub fn new() -> Self {
+        ArrayString {
+            vec: ArrayVec::new(),
+        }
+    }
+
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        A::len()
+    }
+
+    #[inline]
+    pub fn as_str(&self) -> &str {
+        self
+    }
+
+    #[inline]
+    pub fn as_mut_str(&mut self) -> &mut str {
+        self
+    }
+
+    #[inline]
+    pub fn as_vec(&self) -> &ArrayVec<A> {
+        &self.vec
+    }
+
+    #[inline]
+    pub fn into_bytes(self) -> ArrayVec<A> {
+        self.vec
+    }
+
+    pub fn from_utf8(vec: ArrayVec<A>) -> Result<Self, str::Utf8Error> {
+        str::from_utf8(&vec)?;
+        Ok(ArrayString {
+            vec,
+        })
+    }
+
+    pub fn try_push_str<'a>(&mut self, s: &'a str) -> Result<(), CapacityError<&'a str>> {
+        match self.vec.try_extend_from_slice(s.as_bytes()) {
+            Ok(()) => Ok(()),
+            Err(..) => Err(CapacityError::new(s)),
+        }
+    }
+
+    #[inline]
+    pub fn push_str(&mut self, s: &str) {
+        self.try_push_str(s).expect("ArrayString capacity exceeded")
+    }
+
+    #[inline]
+    pub fn try_push(&mut self, c: char) -> Result<(), CapacityError<char>> {
+        let mut buf = [0u8; 4];
+        match self.try_push_str(c.encode_utf8(&mut buf)) {
+            Ok(()) => Ok(()),
+            Err(..) => Err(CapacityError::new(c)),
+        }
+    }
+
+    #[inline]
+    pub fn push(&mut self, c: char) {
+        self.try_push(c).expect("ArrayString capacity exceeded")
+    }
+
+    pub fn pop(&mut self) -> Option<char> {
+        let c = self.chars().next_back()?;
+        let len = self.len() - c.len_utf8();
+        unsafe { self.vec.set_len(len) }
+        Some(c)
+    }
+
+    pub fn truncate(&mut self, new_len: usize) {
+        if new_len < self.len() {
+            assert!(self.is_char_boundary(new_len));
+            unsafe { self.vec.set_len(new_len) }
+        }
+    }
+
+    #[inline]
+    pub fn clear(&mut self) {
+        self.vec.clear()
+    }
+}
+
+impl<A: Array<Item=u8>> Deref for ArrayString<A> {
+    type Target = str;
+
+    #[inline]
+    fn deref(&self) -> &str {
+        unsafe { str::from_utf8_unchecked(&self.vec) }
+    }
+}
+
+impl<A: Array<Item=u8>> DerefMut for ArrayString<A> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut str {
+        unsafe { str::from_utf8_unchecked_mut(&mut self.vec) }
+    }
+}
+
+impl<A: Array<Item=u8>> AsRef<str> for ArrayString<A> {
+    #[inline]
+    fn as_ref(&self) -> &str {
+        self
+    }
+}
+
+impl<A: Array<Item=u8>> AsRef<[u8]> for ArrayString<A> {
+    #[inline]
+    fn as_ref(&self) -> &[u8] {
+        self.as_bytes()
+    }
+}
+
+impl<A: Array<Item=u8>> Borrow<str> for ArrayString<A> {
+    #[inline]
+    fn borrow(&self) -> &str {
+        self
+    }
+}
+
+impl<A: Array<Item=u8>> BorrowMut<str> for ArrayString<A> {
+    #[inline]
+    fn borrow_mut(&mut self) -> &mut str {
+        self
+    }
+}
+
+impl<A: Array<Item=u8>> fmt::Write for ArrayString<A> {
+    #[inline]
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.try_push_str(s).map_err(|_| fmt::Error)
+    }
+
+    #[inline]
+    fn write_char(&mut self, c: char) -> fmt::Result {
+        self.try_push(c).map_err(|_| fmt::Error)
+    }
+}
+
+impl<A: Array<Item=u8>> FromStr for ArrayString<A> {
+    type Err = CapacityError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut v = Self::new();
+        v.try_push_str(s).map_err(CapacityError::simplify)?;
+        Ok(v)
+    }
+}
+
+impl<A: Array<Item=u8>> fmt::Debug for ArrayString<A> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(&**self, fmt)
+    }
+}
+
+impl<A: Array<Item=u8>> fmt::Display for ArrayString<A> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&**self, fmt)
+    }
+}
+
+impl<A: Array<Item=u8>> Hash for ArrayString<A> {
+    fn hash<H: Hasher>(&self, h: &mut H) {
+        (**self).hash(h)
+    }
+}
+
+impl<A: Array<Item=u8>> Clone for ArrayString<A> {
+    fn clone(&self) -> Self {
+        ArrayString {
+            vec: self.vec.clone(),
+        }
+    }
+}
+
+impl<A: Array<Item=u8>> Default for ArrayString<A> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<A: Array<Item=u8>, B: Array<Item=u8>> PartialEq<ArrayString<B>> for ArrayString<A> {
+    fn eq(&self, other: &ArrayString<B>) -> bool {
+        **self == **other
+    }
+}
+
+impl<A: Array<Item=u8>> PartialEq<str> for ArrayString<A> {
+    fn eq(&self, other: &str) -> bool {
+        **self == *other
+    }
+}
+
+impl<'a, A: Array<Item=u8>> PartialEq<&'a str> for ArrayString<A> {
+    fn eq(&self, other: &&'a str) -> bool {
+        **self == **other
+    }
+}
+
+impl<A: Array<Item=u8>> PartialEq<ArrayString<A>> for str {
+    fn eq(&self, other: &ArrayString<A>) -> bool {
+        *self == **other
+    }
+}
+
+impl<A: Array<Item=u8>> Eq for ArrayString<A> { }
+
+impl<A: Array<Item=u8>, B: Array<Item=u8>> PartialOrd<ArrayString<B>> for ArrayString<A> {
+    fn partial_cmp(&self, other: &ArrayString<B>) -> Option<Ordering> {
+        (**self).partial_cmp(&**other)
+    }
+}
+
+impl<A: Array<Item=u8>> PartialOrd<str> for ArrayString<A> {
+    fn partial_cmp(&self, other: &str) -> Option<Ordering> {
+        (**self).partial_cmp(other)
+    }
+}
+
+impl<A: Array<Item=u8>> Ord for ArrayString<A> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (**self).cmp(&**other)
+    }
+}
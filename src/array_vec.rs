@@ -1,14 +1,25 @@
-use std::fmt;
-use std::hash::{Hash, Hasher};
-use std::mem::{MaybeUninit, size_of};
-use std::ptr::{read, drop_in_place};
-use std::ops::{Deref, DerefMut};
-use std::slice::{self, from_raw_parts, from_raw_parts_mut};
-use std::iter::FromIterator;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::mem::{MaybeUninit, size_of};
+use core::ptr::{read, drop_in_place};
+use core::ops::{Deref, DerefMut, Index, IndexMut, Range, RangeFrom, RangeTo, RangeFull};
+use core::slice::{self, from_raw_parts, from_raw_parts_mut};
+use core::iter::FromIterator;
 use crate::vector::Vector;
 use crate::array::{Array, ArrayIndex};
+use crate::error::CapacityError;
 use crate::util::PointerExt;
 
+/// Formats into a byte `ArrayVec` of the given capacity, like `format!`.
+///
+/// Evaluates to `Result<ArrayVec<[u8; N]>, CapacityError>`.
+#[macro_export]
+macro_rules! format_array {
+    ($n:expr, $($arg:tt)*) => {
+        $crate::ArrayVec::<[u8; $n]>::try_format(::core::format_args!($($arg)*))
+    };
+}
+
 pub struct ArrayVec<T: Array> {
     array: MaybeUninit<T>,
     len: T::Index,
@@ -35,6 +46,88 @@ impl<T: Array> ArrayVec<T> {
             Err(self)
         }
     }
+
+    pub fn try_with_capacity(cap: usize) -> Result<Self, CapacityError> {
+        if cap <= T::len() {
+            Ok(Self::new())
+        } else {
+            Err(CapacityError::new(()))
+        }
+    }
+
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), CapacityError> {
+        if T::len() - self.len() >= additional {
+            Ok(())
+        } else {
+            Err(CapacityError::new(()))
+        }
+    }
+
+    pub fn try_push(&mut self, value: T::Item) -> Result<(), CapacityError<T::Item>> {
+        if self.len() < T::len() {
+            self.push(value);
+            Ok(())
+        } else {
+            Err(CapacityError::new(value))
+        }
+    }
+
+    pub fn try_insert(&mut self, index: usize, element: T::Item) -> Result<(), CapacityError<T::Item>> {
+        assert!(index <= self.len());
+        if self.len() < T::len() {
+            self.insert(index, element);
+            Ok(())
+        } else {
+            Err(CapacityError::new(element))
+        }
+    }
+
+    pub fn try_extend_from_slice(&mut self, other: &[T::Item]) -> Result<(), CapacityError> where T::Item: Clone {
+        self.try_reserve(other.len())?;
+        self.extend(other.iter().cloned());
+        Ok(())
+    }
+}
+
+impl<T: Array<Item=u8>> ArrayVec<T> {
+    /// Formats `args` into a new vector, failing if the output doesn't fit.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a formatting trait implementation returns an error, as
+    /// `format!` does.
+    pub fn try_format(args: fmt::Arguments) -> Result<Self, CapacityError> {
+        struct Writer<T: Array<Item=u8>> {
+            vec: ArrayVec<T>,
+            overflow: bool,
+        }
+
+        impl<T: Array<Item=u8>> fmt::Write for Writer<T> {
+            fn write_str(&mut self, s: &str) -> fmt::Result {
+                self.vec.try_extend_from_slice(s.as_bytes()).map_err(|_| {
+                    self.overflow = true;
+                    fmt::Error
+                })
+            }
+        }
+
+        let mut w = Writer {
+            vec: Self::new(),
+            overflow: false,
+        };
+        match fmt::write(&mut w, args) {
+            Ok(()) => Ok(w.vec),
+            Err(..) if w.overflow => Err(CapacityError::new(())),
+            Err(..) => panic!("a formatting trait implementation returned an error"),
+        }
+    }
+}
+
+impl<T: Array<Item=u8>> fmt::Write for ArrayVec<T> {
+    #[inline]
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.try_extend_from_slice(s.as_bytes()).map_err(|_| fmt::Error)
+    }
 }
 
 unsafe impl<T: Array> Vector for ArrayVec<T> {
@@ -59,8 +152,6 @@ unsafe impl<T: Array> Vector for ArrayVec<T> {
     #[inline]
     fn shrink_to_fit(&mut self) { }
 
-    fn into_boxed_slice(self) -> Box<[T::Item]> { unimplemented!() }
-
     #[inline]
     unsafe fn set_len(&mut self, len: usize) { self.len = ArrayIndex::from_usize(len); }
 
@@ -82,10 +173,9 @@ impl<T: Array> Drop for ArrayVec<T> {
     fn drop(&mut self) {
         let len = ArrayIndex::to_usize(self.len);
         if len > 0 {
-            let ptr = Array::as_uninit_mut(&mut self.array).as_mut_ptr() as *mut T::Item;
-
             unsafe {
                 self.set_len(0);
+                let ptr = self.as_mut_ptr();
                 for i in 0..len {
                     drop_in_place(ptr.uoffset(i));
                 }
@@ -103,6 +193,14 @@ impl<T: Array> From<T> for ArrayVec<T> {
     }
 }
 
+#[cfg(feature = "alloc")]
+impl<T: Array> From<ArrayVec<T>> for alloc::vec::Vec<T::Item> {
+    #[inline]
+    fn from(v: ArrayVec<T>) -> Self {
+        v.into_vec()
+    }
+}
+
 pub struct ArrayVecIntoIter<T: Array> {
     inner: ArrayVec<T>,
     start: T::Index,
@@ -269,3 +367,42 @@ impl<T: Array> DerefMut for ArrayVec<T> {
         unsafe { from_raw_parts_mut(Array::as_uninit_mut(&mut self.array).as_mut_ptr() as *mut _, ArrayIndex::to_usize(self.len)) }
     }
 }
+
+impl<T: Array> AsRef<[T::Item]> for ArrayVec<T> {
+    #[inline]
+    fn as_ref(&self) -> &[T::Item] {
+        self
+    }
+}
+
+impl<T: Array> AsMut<[T::Item]> for ArrayVec<T> {
+    #[inline]
+    fn as_mut(&mut self) -> &mut [T::Item] {
+        self
+    }
+}
+
+macro_rules! index_impl {
+    ($t:ty) => {
+        impl<T: Array> Index<$t> for ArrayVec<T> where [T::Item]: Index<$t> {
+            type Output = <[T::Item] as Index<$t>>::Output;
+
+            fn index(&self, index: $t) -> &Self::Output {
+                Index::index(&**self, index)
+            }
+        }
+
+        impl<T: Array> IndexMut<$t> for ArrayVec<T> {
+            fn index_mut(&mut self, index: $t) -> &mut Self::Output {
+                IndexMut::index_mut(&mut **self, index)
+            }
+        }
+    };
+    ($($t:ty),+) => {
+        $(
+            index_impl!($t);
+        )+
+    };
+}
+
+index_impl!(usize, Range<usize>, RangeFrom<usize>, RangeTo<usize>, RangeFull);
@@ -0,0 +1,344 @@
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::iter::{FromIterator, FusedIterator};
+use core::ops::Range;
+use crate::{Array, ArrayIndex, ArrayVec, Vector, CapacityError};
+
+const BITS: usize = usize::BITS as usize;
+
+// Wide enough for the bits of any array the stable `Array` impls cover.
+type BitIndex = u32;
+
+pub type BitArrayVec<A> = BitVector<ArrayVec<A>>;
+#[cfg(feature = "alloc")]
+pub type SmallBitVec<A> = BitVector<crate::SmallVec<A>>;
+
+/// A vector of bits packed into the words of a `Vector<Item=usize>`.
+///
+/// Bits past the length in the last word are always zero.
+pub struct BitVector<S> {
+    words: S,
+    len: BitIndex,
+}
+
+impl<S: Vector<Item=usize>> BitVector<S> {
+    #[inline]
+    pub fn new() -> Self {
+        BitVector {
+            words: S::new(),
+            len: 0,
+        }
+    }
+
+    #[inline]
+    pub fn with_capacity(bits: usize) -> Self {
+        BitVector {
+            words: S::with_capacity(bits.div_ceil(BITS)),
+            len: 0,
+        }
+    }
+
+    #[inline]
+    pub fn as_words(&self) -> &[usize] {
+        self.words.as_slice()
+    }
+
+    #[inline]
+    pub fn into_words(self) -> S {
+        self.words
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len.to_usize()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.words.capacity().saturating_mul(BITS)
+    }
+
+    #[inline]
+    pub fn get(&self, index: usize) -> Option<bool> {
+        if index < self.len() {
+            Some(self.words.as_slice()[index / BITS] & (1 << (index % BITS)) != 0)
+        } else {
+            None
+        }
+    }
+
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    #[inline]
+    pub fn set(&mut self, index: usize, value: bool) {
+        assert!(index < self.len(), "bit index {} out of bounds for length {}", index, self.len());
+        let word = &mut self.words.as_mut_slice()[index / BITS];
+        let mask = 1 << (index % BITS);
+        if value {
+            *word |= mask;
+        } else {
+            *word &= !mask;
+        }
+    }
+
+    /// # Panics
+    ///
+    /// Panics if the underlying vector is out of capacity, or if the length
+    /// would overflow a `u32`.
+    #[allow(clippy::manual_is_multiple_of)]
+    pub fn push(&mut self, value: bool) {
+        let len = self.len();
+        assert!(len < BitIndex::MAX as usize, "bit vector length overflow");
+        if len % BITS == 0 {
+            self.words.push(0);
+        }
+        self.len = BitIndex::from_usize(len + 1);
+        self.set(len, value);
+    }
+
+    pub fn pop(&mut self) -> Option<bool> {
+        let len = self.len().checked_sub(1)?;
+        let value = self.get(len);
+        self.truncate(len);
+        value
+    }
+
+    #[allow(clippy::manual_is_multiple_of)]
+    pub fn truncate(&mut self, len: usize) {
+        if len < self.len() {
+            self.words.truncate(len.div_ceil(BITS));
+            if len % BITS != 0 {
+                if let Some(word) = self.words.as_mut_slice().last_mut() {
+                    *word &= (1 << (len % BITS)) - 1;
+                }
+            }
+            self.len = BitIndex::from_usize(len);
+        }
+    }
+
+    #[inline]
+    pub fn clear(&mut self) {
+        self.truncate(0)
+    }
+
+    #[inline]
+    pub fn count_ones(&self) -> usize {
+        self.words.as_slice().iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    #[inline]
+    pub fn count_zeros(&self) -> usize {
+        self.len() - self.count_ones()
+    }
+
+    #[inline]
+    pub fn any(&self) -> bool {
+        self.words.as_slice().iter().any(|&w| w != 0)
+    }
+
+    #[inline]
+    pub fn none(&self) -> bool {
+        !self.any()
+    }
+
+    fn zip_words<T: Vector<Item=usize>, F: Fn(usize, usize) -> usize>(&mut self, other: &BitVector<T>, f: F) {
+        assert_eq!(self.len(), other.len(), "bit vectors differ in length");
+        for (a, &b) in self.words.as_mut_slice().iter_mut().zip(other.words.as_slice()) {
+            *a = f(*a, b);
+        }
+    }
+
+    /// Sets every bit that is set in `other`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the lengths differ.
+    #[inline]
+    pub fn union_with<T: Vector<Item=usize>>(&mut self, other: &BitVector<T>) {
+        self.zip_words(other, |a, b| a | b)
+    }
+
+    /// Clears every bit that is not set in `other`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the lengths differ.
+    #[inline]
+    pub fn intersect_with<T: Vector<Item=usize>>(&mut self, other: &BitVector<T>) {
+        self.zip_words(other, |a, b| a & b)
+    }
+
+    /// Clears every bit that is set in `other`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the lengths differ.
+    #[inline]
+    pub fn difference_with<T: Vector<Item=usize>>(&mut self, other: &BitVector<T>) {
+        self.zip_words(other, |a, b| a & !b)
+    }
+
+    #[inline]
+    pub fn iter(&self) -> BitVectorIter<'_> {
+        BitVectorIter {
+            words: self.words.as_slice(),
+            range: 0..self.len(),
+        }
+    }
+
+    /// Iterates over the indices of the set bits.
+    #[inline]
+    pub fn ones(&self) -> BitVectorOnes<'_> {
+        let words = self.words.as_slice();
+        BitVectorOnes {
+            current: words.first().cloned().unwrap_or(0),
+            base: 0,
+            words: words.get(1..).unwrap_or(&[]),
+        }
+    }
+}
+
+impl<A: Array<Item=usize>> BitVector<ArrayVec<A>> {
+    pub fn try_push(&mut self, value: bool) -> Result<(), CapacityError<bool>> {
+        if self.len() < self.capacity() {
+            self.push(value);
+            Ok(())
+        } else {
+            Err(CapacityError::new(value))
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct BitVectorIter<'a> {
+    words: &'a [usize],
+    range: Range<usize>,
+}
+
+impl<'a> BitVectorIter<'a> {
+    #[inline]
+    fn bit(&self, index: usize) -> bool {
+        self.words[index / BITS] & (1 << (index % BITS)) != 0
+    }
+}
+
+impl<'a> Iterator for BitVectorIter<'a> {
+    type Item = bool;
+
+    #[inline]
+    fn next(&mut self) -> Option<bool> {
+        self.range.next().map(|i| self.bit(i))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.range.size_hint()
+    }
+}
+
+impl<'a> DoubleEndedIterator for BitVectorIter<'a> {
+    #[inline]
+    fn next_back(&mut self) -> Option<bool> {
+        self.range.next_back().map(|i| self.bit(i))
+    }
+}
+
+impl<'a> ExactSizeIterator for BitVectorIter<'a> { }
+impl<'a> FusedIterator for BitVectorIter<'a> { }
+
+#[derive(Clone)]
+pub struct BitVectorOnes<'a> {
+    current: usize,
+    base: usize,
+    words: &'a [usize],
+}
+
+impl<'a> Iterator for BitVectorOnes<'a> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        while self.current == 0 {
+            let (&word, words) = self.words.split_first()?;
+            self.current = word;
+            self.words = words;
+            self.base += BITS;
+        }
+
+        let bit = self.current.trailing_zeros() as usize;
+        self.current &= self.current - 1;
+        Some(self.base + bit)
+    }
+}
+
+impl<'a> FusedIterator for BitVectorOnes<'a> { }
+
+impl<'a, S: Vector<Item=usize>> IntoIterator for &'a BitVector<S> {
+    type Item = bool;
+    type IntoIter = BitVectorIter<'a>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<S: Vector<Item=usize>> Extend<bool> for BitVector<S> {
+    fn extend<I: IntoIterator<Item=bool>>(&mut self, iter: I) {
+        for v in iter {
+            self.push(v);
+        }
+    }
+}
+
+impl<S: Vector<Item=usize>> FromIterator<bool> for BitVector<S> {
+    fn from_iter<I: IntoIterator<Item=bool>>(iter: I) -> Self {
+        let mut v = Self::new();
+        v.extend(iter);
+        v
+    }
+}
+
+impl<S: Clone> Clone for BitVector<S> {
+    fn clone(&self) -> Self {
+        BitVector {
+            words: self.words.clone(),
+            len: self.len,
+        }
+    }
+}
+
+impl<S: Vector<Item=usize>> Default for BitVector<S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S: Vector<Item=usize>> fmt::Debug for BitVector<S> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        for bit in self {
+            fmt::Write::write_char(fmt, if bit { '1' } else { '0' })?;
+        }
+        Ok(())
+    }
+}
+
+impl<S: Vector<Item=usize>> Hash for BitVector<S> {
+    fn hash<H: Hasher>(&self, h: &mut H) {
+        self.len().hash(h);
+        self.as_words().hash(h);
+    }
+}
+
+impl<S: Vector<Item=usize>, T: Vector<Item=usize>> PartialEq<BitVector<T>> for BitVector<S> {
+    fn eq(&self, other: &BitVector<T>) -> bool {
+        self.len() == other.len() && self.as_words() == other.as_words()
+    }
+}
+
+impl<S: Vector<Item=usize>> Eq for BitVector<S> { }
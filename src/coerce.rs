@@ -0,0 +1,96 @@
+#[cfg(feature = "unstable")]
+use core::marker::Unsize;
+
+/// Unsizes a reference to `Self` into a reference to `T`.
+///
+/// With the `unstable` feature this is implemented for every `Unsize<T>`
+/// type. Otherwise it covers arrays as slices, and `dyn Any`, `dyn Debug`
+/// and closures of up to six arguments as `dyn Fn`, `dyn FnMut` or
+/// `dyn FnOnce`, each optionally `+ Send` or `+ Send + Sync`.
+///
+/// # Safety
+///
+/// `coerce` must return `self` unsized to `T`, with the same address,
+/// size and alignment.
+pub unsafe trait Coerce<T: ?Sized> {
+    fn coerce(&self) -> &T;
+
+    /// Returns a shim that calls a `dyn FnOnce` by value, used by
+    /// `SmallDST::call_once` to call the value in place.
+    #[doc(hidden)]
+    #[inline]
+    fn call_once_shim() -> Option<CallOnceShim> {
+        None
+    }
+}
+
+/// Moves the closure out of the first pointer and calls it with the argument
+/// tuple moved out of the second, writing the result to the third.
+#[doc(hidden)]
+pub type CallOnceShim = unsafe fn(*mut u8, *mut u8, *mut u8);
+
+#[cfg(feature = "unstable")]
+unsafe impl<T: ?Sized, U: Unsize<T>> Coerce<T> for U {
+    #[inline]
+    fn coerce(&self) -> &T { self }
+}
+
+#[cfg(not(feature = "unstable"))]
+mod impls {
+    use core::any::Any;
+    use core::fmt::Debug;
+    use core::ptr::{read, write};
+    use super::{Coerce, CallOnceShim};
+
+    unsafe impl<T, const N: usize> Coerce<[T]> for [T; N] {
+        #[inline]
+        fn coerce(&self) -> &[T] { self }
+    }
+
+    macro_rules! coerce_impl {
+        ([$($g:tt)*] [$($b:tt)*] $t:ty { $($body:tt)* }) => {
+            unsafe impl<$($g)*> Coerce<$t> for U where U: $($b)* {
+                #[inline]
+                fn coerce(&self) -> &$t { self }
+
+                $($body)*
+            }
+        };
+        ([$($g:tt)*] [$($b:tt)*] { $($body:tt)* }) => {
+            coerce_impl!([$($g)*] [$($b)*] dyn $($b)* { $($body)* });
+            coerce_impl!([$($g)*] [$($b)* + Send] dyn $($b)* + Send { $($body)* });
+            coerce_impl!([$($g)*] [$($b)* + Send + Sync] dyn $($b)* + Send + Sync { $($body)* });
+        };
+    }
+
+    macro_rules! coerce_fn_impl {
+        ($($a:ident),*) => {
+            coerce_impl!(['a, U, R, $($a),*] [Fn($($a),*) -> R + 'a] { });
+            coerce_impl!(['a, U, R, $($a),*] [FnMut($($a),*) -> R + 'a] { });
+            coerce_impl!(['a, U, R, $($a),*] [FnOnce($($a),*) -> R + 'a] {
+                #[inline]
+                fn call_once_shim() -> Option<CallOnceShim> {
+                    #[allow(non_snake_case)]
+                    unsafe fn shim<U: FnOnce($($a),*) -> R, R, $($a),*>(f: *mut u8, args: *mut u8, ret: *mut u8) {
+                        let f = read(f as *mut U);
+                        let ($($a,)*) = read(args as *mut ($($a,)*));
+                        write(ret as *mut R, f($($a),*));
+                    }
+
+                    Some(shim::<U, R, $($a),*>)
+                }
+            });
+        };
+    }
+
+    coerce_impl!([U] [Any] { });
+    coerce_impl!(['a, U] [Debug + 'a] { });
+
+    coerce_fn_impl!();
+    coerce_fn_impl!(A1);
+    coerce_fn_impl!(A1, A2);
+    coerce_fn_impl!(A1, A2, A3);
+    coerce_fn_impl!(A1, A2, A3, A4);
+    coerce_fn_impl!(A1, A2, A3, A4, A5);
+    coerce_fn_impl!(A1, A2, A3, A4, A5, A6);
+}
@@ -0,0 +1,322 @@
+use core::mem::replace;
+use core::ptr::{NonNull, read, write, copy, drop_in_place, slice_from_raw_parts_mut};
+use core::ops::{Range, RangeBounds};
+use core::iter::FusedIterator;
+use core::marker::PhantomData;
+#[cfg(feature = "alloc")]
+use alloc::vec::{self, Vec};
+#[cfg(feature = "alloc")]
+use coalesce::{Coalesce2, coalesce};
+use crate::vector::Vector;
+use crate::util::{PointerExt, range_bounds};
+
+// `Vec` forwards to its own drain and splice, which only exist with `alloc`.
+#[cfg(feature = "alloc")]
+type DrainInner<'a, V> = Coalesce2<RawDrain<'a, V>, vec::Drain<'a, <V as Vector>::Item>>;
+#[cfg(not(feature = "alloc"))]
+type DrainInner<'a, V> = RawDrain<'a, V>;
+
+#[cfg(feature = "alloc")]
+type SpliceInner<'a, V, I> = Coalesce2<RawSplice<'a, V, I>, vec::Splice<'a, I>>;
+#[cfg(not(feature = "alloc"))]
+type SpliceInner<'a, V, I> = RawSplice<'a, V, I>;
+
+#[cfg(feature = "alloc")]
+macro_rules! forward {
+    (ref mut $s:expr => $f:ident()) => {{ let v = $s.as_mut(); coalesce!(2 => |v| v.$f()) }};
+    (ref $s:expr => $f:ident()) => {{ let v = $s.as_ref(); coalesce!(2 => |v| v.$f()) }};
+}
+
+#[cfg(not(feature = "alloc"))]
+macro_rules! forward {
+    (ref mut $s:expr => $f:ident()) => { $s.$f() };
+    (ref $s:expr => $f:ident()) => { $s.$f() };
+}
+
+pub struct Drain<'a, V: Vector + 'a>(DrainInner<'a, V>);
+
+impl<'a, V: Vector + 'a> Drain<'a, V> {
+    pub(crate) fn new<R: RangeBounds<usize>>(vec: &'a mut V, range: R) -> Self {
+        let raw = RawDrain::new(vec, range);
+        #[cfg(feature = "alloc")]
+        let raw = Coalesce2::A(raw);
+        Drain(raw)
+    }
+
+    #[cfg(feature = "alloc")]
+    pub(crate) fn from_vec(drain: vec::Drain<'a, V::Item>) -> Self {
+        Drain(Coalesce2::B(drain))
+    }
+}
+
+impl<'a, V: Vector + 'a> Iterator for Drain<'a, V> {
+    type Item = V::Item;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        forward!(ref mut self.0 => next())
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        forward!(ref self.0 => size_hint())
+    }
+}
+
+impl<'a, V: Vector + 'a> DoubleEndedIterator for Drain<'a, V> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        forward!(ref mut self.0 => next_back())
+    }
+}
+
+impl<'a, V: Vector + 'a> ExactSizeIterator for Drain<'a, V> { }
+
+impl<'a, V: Vector + 'a> FusedIterator for Drain<'a, V> { }
+
+// The vector's length is cut to the start of the range for the lifetime of
+// the drain, so forgetting it only leaks the drained range and the tail.
+struct RawDrain<'a, V: Vector + 'a> {
+    vec: NonNull<V>,
+    // the drained items are read and dropped through `base`, which must not
+    // be used once the vector has been touched again
+    base: *mut V::Item,
+    remaining: Range<usize>,
+    tail_start: usize,
+    tail_len: usize,
+    _phantom: PhantomData<&'a mut V>,
+}
+
+impl<'a, V: Vector + 'a> RawDrain<'a, V> {
+    fn new<R: RangeBounds<usize>>(vec: &'a mut V, range: R) -> Self {
+        let len = vec.len();
+        let (start, end) = range_bounds(range, len);
+
+        unsafe {
+            vec.set_len(start);
+            let mut vec = NonNull::from(vec);
+            let base = vec.as_mut().as_mut_ptr();
+
+            RawDrain {
+                vec,
+                base,
+                remaining: start..end,
+                tail_start: end,
+                tail_len: len - end,
+                _phantom: PhantomData,
+            }
+        }
+    }
+
+    // Fills the gap between the vector's length and the tail, returning
+    // false if `iter` runs out first.
+    unsafe fn fill<I: Iterator<Item=V::Item>>(&mut self, iter: &mut I) -> bool {
+        let vec = self.vec.as_mut();
+        while vec.len() < self.tail_start {
+            match iter.next() {
+                Some(v) => {
+                    let len = vec.len();
+                    write(vec.as_mut_ptr().uoffset(len), v);
+                    vec.set_len(len + 1);
+                },
+                None => return false,
+            }
+        }
+        true
+    }
+
+    // Widens the gap before the tail by `additional` items.
+    //
+    // Only called once `fill` has closed the gap, so the tail directly follows
+    // the vector's items and extending the length over it claims no
+    // uninitialized slots. If `reserve` panics, the guard cuts the length back
+    // and the tail is still where the drain expects it.
+    unsafe fn move_tail_by(&mut self, additional: usize) {
+        struct LenGuard<'v, V: Vector>(&'v mut V, usize);
+
+        impl<'v, V: Vector> Drop for LenGuard<'v, V> {
+            fn drop(&mut self) {
+                unsafe { self.0.set_len(self.1) }
+            }
+        }
+
+        let vec = self.vec.as_mut();
+        let len = vec.len();
+        assert_eq!(len, self.tail_start);
+        // the tail must be within the length so that a spill carries it along
+        vec.set_len(len + self.tail_len);
+        {
+            let guard = LenGuard(vec, len);
+            guard.0.reserve(additional);
+        }
+
+        let ptr = vec.as_mut_ptr();
+        let tail_start = self.tail_start + additional;
+        copy(ptr.uoffset(self.tail_start), ptr.uoffset(tail_start), self.tail_len);
+        self.tail_start = tail_start;
+    }
+
+    fn move_tail(&mut self) {
+        unsafe {
+            let vec = self.vec.as_mut();
+            let start = vec.len();
+            if self.tail_len > 0 && self.tail_start != start {
+                let ptr = vec.as_mut_ptr();
+                copy(ptr.uoffset(self.tail_start), ptr.uoffset(start), self.tail_len);
+            }
+            vec.set_len(start + self.tail_len);
+        }
+    }
+}
+
+unsafe impl<'a, V: Vector + Send + 'a> Send for RawDrain<'a, V> where V::Item: Send { }
+
+unsafe impl<'a, V: Vector + Sync + 'a> Sync for RawDrain<'a, V> where V::Item: Sync { }
+
+impl<'a, V: Vector + 'a> Iterator for RawDrain<'a, V> {
+    type Item = V::Item;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.remaining.next().map(|i| unsafe { read(self.base.uoffset(i)) })
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.remaining.size_hint()
+    }
+}
+
+impl<'a, V: Vector + 'a> DoubleEndedIterator for RawDrain<'a, V> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.remaining.next_back().map(|i| unsafe { read(self.base.uoffset(i)) })
+    }
+}
+
+impl<'a, V: Vector + 'a> Drop for RawDrain<'a, V> {
+    fn drop(&mut self) {
+        struct TailGuard<'r, 'a, V: Vector + 'a>(&'r mut RawDrain<'a, V>);
+
+        impl<'r, 'a, V: Vector + 'a> Drop for TailGuard<'r, 'a, V> {
+            fn drop(&mut self) {
+                self.0.move_tail();
+            }
+        }
+
+        let remaining = replace(&mut self.remaining, 0..0);
+        let base = self.base;
+        let _guard = TailGuard(self);
+        unsafe {
+            drop_in_place(slice_from_raw_parts_mut(base.uoffset(remaining.start), remaining.len()));
+        }
+    }
+}
+
+pub struct Splice<'a, V: Vector + 'a, I: Iterator<Item=V::Item> + 'a>(SpliceInner<'a, V, I>);
+
+impl<'a, V: Vector + 'a, I: Iterator<Item=V::Item> + 'a> Splice<'a, V, I> {
+    pub(crate) fn new<R: RangeBounds<usize>>(vec: &'a mut V, range: R, replace_with: I) -> Self {
+        let raw = RawSplice {
+            drain: RawDrain::new(vec, range),
+            replace_with,
+        };
+        #[cfg(feature = "alloc")]
+        let raw = Coalesce2::A(raw);
+        Splice(raw)
+    }
+
+    #[cfg(feature = "alloc")]
+    pub(crate) fn from_vec(splice: vec::Splice<'a, I>) -> Self {
+        Splice(Coalesce2::B(splice))
+    }
+}
+
+impl<'a, V: Vector + 'a, I: Iterator<Item=V::Item> + 'a> Iterator for Splice<'a, V, I> {
+    type Item = V::Item;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        forward!(ref mut self.0 => next())
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        forward!(ref self.0 => size_hint())
+    }
+}
+
+impl<'a, V: Vector + 'a, I: Iterator<Item=V::Item> + 'a> DoubleEndedIterator for Splice<'a, V, I> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        forward!(ref mut self.0 => next_back())
+    }
+}
+
+struct RawSplice<'a, V: Vector + 'a, I: Iterator<Item=V::Item>> {
+    drain: RawDrain<'a, V>,
+    replace_with: I,
+}
+
+impl<'a, V: Vector + 'a, I: Iterator<Item=V::Item>> Iterator for RawSplice<'a, V, I> {
+    type Item = V::Item;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.drain.next()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.drain.size_hint()
+    }
+}
+
+impl<'a, V: Vector + 'a, I: Iterator<Item=V::Item>> DoubleEndedIterator for RawSplice<'a, V, I> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.drain.next_back()
+    }
+}
+
+impl<'a, V: Vector + 'a, I: Iterator<Item=V::Item>> Drop for RawSplice<'a, V, I> {
+    fn drop(&mut self) {
+        self.drain.by_ref().for_each(drop);
+
+        unsafe {
+            if self.drain.tail_len == 0 {
+                let vec = self.drain.vec.as_mut();
+                self.replace_with.by_ref().for_each(|v| vec.push(v));
+                return
+            }
+
+            if !self.drain.fill(&mut self.replace_with) {
+                return
+            }
+
+            let (lower, _) = self.replace_with.size_hint();
+            if lower > 0 {
+                self.drain.move_tail_by(lower);
+                if !self.drain.fill(&mut self.replace_with) {
+                    return
+                }
+            }
+
+            #[cfg(feature = "alloc")]
+            {
+                let mut rest = self.replace_with.by_ref().collect::<Vec<_>>().into_iter();
+                if rest.len() > 0 {
+                    self.drain.move_tail_by(rest.len());
+                    self.drain.fill(&mut rest);
+                }
+            }
+
+            // without a buffer, make room for one item at a time
+            #[cfg(not(feature = "alloc"))]
+            for v in self.replace_with.by_ref() {
+                self.drain.move_tail_by(1);
+                self.drain.fill(&mut Some(v).into_iter());
+            }
+        }
+    }
+}
@@ -0,0 +1,259 @@
+use core::fmt;
+use core::marker::PhantomData;
+use core::alloc::Layout;
+use core::mem::{MaybeUninit, ManuallyDrop, size_of, size_of_val, align_of, align_of_val};
+use core::ptr::{copy_nonoverlapping, drop_in_place, write};
+use core::slice::from_raw_parts;
+use crate::small_dst::SmallDST;
+use crate::util::PointerExt;
+use crate::{ArrayVec, Vector, Coerce};
+
+// Each entry is laid out as the pointer metadata words, followed by the
+// value rounded up to whole words, followed by the entry length in words.
+pub struct DstStack<T: ?Sized, A: Vector<Item=usize> = ArrayVec<[usize; 32]>> {
+    data: A,
+    len: usize,
+    _phantom: PhantomData<T>,
+}
+
+impl<T: ?Sized, A: Vector<Item=usize>> DstStack<T, A> {
+    #[inline]
+    pub fn new() -> Self {
+        Self::with_capacity(0)
+    }
+
+    #[inline]
+    pub fn with_capacity(words: usize) -> Self {
+        DstStack {
+            data: A::with_capacity(words),
+            len: 0,
+            _phantom: PhantomData,
+        }
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    fn meta_len() -> usize {
+        assert_eq!(size_of::<*const T>() % size_of::<usize>(), 0);
+        size_of::<*const T>() / size_of::<usize>() - 1
+    }
+
+    fn entry_len(v: &T) -> usize {
+        Self::meta_len() + size_of_val(v).div_ceil(size_of::<usize>()) + 1
+    }
+
+    // The data address is written as a pointer so that it keeps the
+    // provenance of `base`.
+    unsafe fn entry_ptr(base: *mut usize, offset: usize) -> *mut T {
+        let mut ptr = MaybeUninit::<*mut T>::uninit();
+        write(ptr.as_mut_ptr() as *mut *mut usize, base.uoffset(offset + Self::meta_len()));
+        copy_nonoverlapping(base.uoffset(offset), (ptr.as_mut_ptr() as *mut usize).uoffset(1), Self::meta_len());
+        ptr.assume_init()
+    }
+
+    fn last_offset(&self) -> Option<usize> {
+        let end = self.data.len();
+        if end == 0 {
+            None
+        } else {
+            Some(end - self.data.as_slice()[end - 1])
+        }
+    }
+
+    pub fn push<U: Coerce<T>>(&mut self, v: U) {
+        let v = ManuallyDrop::new(v);
+        unsafe { self.push_ref(Coerce::<T>::coerce(&*v), &*v) }
+    }
+
+    /// # Safety
+    ///
+    /// `v` must be a reference to `u` unsized to `T`, and `u` must not be
+    /// dropped afterwards as ownership moves into the stack.
+    pub unsafe fn push_ref<U>(&mut self, v: &T, u: &U) {
+        let t = v as *const T;
+        let words = from_raw_parts(&t as *const *const T as *const usize, Self::meta_len() + 1);
+        assert_eq!(words[0], u as *const U as usize);
+        assert_eq!(size_of_val(v), size_of::<U>());
+        assert_eq!(align_of_val(v), align_of::<U>());
+        assert!(align_of::<U>() <= align_of::<usize>(), "value is over-aligned for DstStack storage");
+
+        let entry = Self::entry_len(v);
+        let offset = self.data.len();
+        self.data.reserve(entry);
+
+        let ptr = self.data.as_mut_ptr().uoffset(offset);
+        copy_nonoverlapping(words[1..].as_ptr(), ptr, Self::meta_len());
+        copy_nonoverlapping(u as *const U as *const u8, ptr.uoffset(Self::meta_len()) as *mut u8, size_of::<U>());
+        write(ptr.uoffset(entry - 1), entry);
+        self.data.set_len(offset + entry);
+        self.len += 1;
+    }
+
+    pub fn pop(&mut self) -> Option<SmallDST<T, A>> {
+        let offset = self.last_offset()?;
+        unsafe {
+            let base = self.data.as_mut_ptr();
+            let ptr = Self::entry_ptr(base, offset);
+            let meta = from_raw_parts(base.uoffset(offset), Self::meta_len());
+            let dst = SmallDST::from_raw(ptr as *const u8, Layout::for_value(&*ptr), meta);
+            self.data.set_len(offset);
+            self.len -= 1;
+            Some(dst)
+        }
+    }
+
+    #[inline]
+    pub fn last(&self) -> Option<&T> {
+        let offset = self.last_offset()?;
+        unsafe { Some(&*Self::entry_ptr(self.data.as_ptr() as *mut usize, offset)) }
+    }
+
+    #[inline]
+    pub fn last_mut(&mut self) -> Option<&mut T> {
+        let offset = self.last_offset()?;
+        unsafe { Some(&mut *Self::entry_ptr(self.data.as_mut_ptr(), offset)) }
+    }
+
+    pub fn clear(&mut self) {
+        let end = self.data.len();
+        let mut offset = 0;
+        unsafe {
+            self.data.set_len(0);
+            self.len = 0;
+            let base = self.data.as_mut_ptr();
+            while offset < end {
+                let ptr = Self::entry_ptr(base, offset);
+                offset += Self::entry_len(&*ptr);
+                drop_in_place(ptr);
+            }
+        }
+    }
+
+    #[inline]
+    pub fn iter(&self) -> DstStackIter<'_, T> {
+        DstStackIter {
+            base: self.data.as_ptr(),
+            offset: 0,
+            len: self.len,
+            _phantom: PhantomData,
+        }
+    }
+
+    #[inline]
+    pub fn iter_mut(&mut self) -> DstStackIterMut<'_, T> {
+        DstStackIterMut {
+            base: self.data.as_mut_ptr(),
+            offset: 0,
+            len: self.len,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<T: ?Sized, A: Vector<Item=usize>> Drop for DstStack<T, A> {
+    fn drop(&mut self) {
+        self.clear()
+    }
+}
+
+impl<T: ?Sized, A: Vector<Item=usize>> Default for DstStack<T, A> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: ?Sized + fmt::Debug, A: Vector<Item=usize>> fmt::Debug for DstStack<T, A> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<'a, T: ?Sized, A: Vector<Item=usize>> IntoIterator for &'a DstStack<T, A> {
+    type Item = &'a T;
+    type IntoIter = DstStackIter<'a, T>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T: ?Sized, A: Vector<Item=usize>> IntoIterator for &'a mut DstStack<T, A> {
+    type Item = &'a mut T;
+    type IntoIter = DstStackIterMut<'a, T>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+pub struct DstStackIter<'a, T: ?Sized> {
+    base: *const usize,
+    offset: usize,
+    len: usize,
+    _phantom: PhantomData<&'a T>,
+}
+
+impl<'a, T: ?Sized> Iterator for DstStackIter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None
+        }
+
+        unsafe {
+            let v = &*DstStack::<T>::entry_ptr(self.base as *mut usize, self.offset);
+            self.offset += DstStack::<T>::entry_len(v);
+            self.len -= 1;
+            Some(v)
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<'a, T: ?Sized> ExactSizeIterator for DstStackIter<'a, T> { }
+
+pub struct DstStackIterMut<'a, T: ?Sized> {
+    base: *mut usize,
+    offset: usize,
+    len: usize,
+    _phantom: PhantomData<&'a mut T>,
+}
+
+impl<'a, T: ?Sized> Iterator for DstStackIterMut<'a, T> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None
+        }
+
+        unsafe {
+            let v = &mut *DstStack::<T>::entry_ptr(self.base, self.offset);
+            self.offset += DstStack::<T>::entry_len(v);
+            self.len -= 1;
+            Some(v)
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<'a, T: ?Sized> ExactSizeIterator for DstStackIterMut<'a, T> { }
@@ -0,0 +1,40 @@
+use core::fmt;
+use core::error::Error;
+
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CapacityError<T = ()> {
+    element: T,
+}
+
+impl<T> CapacityError<T> {
+    #[inline]
+    pub fn new(element: T) -> Self {
+        CapacityError {
+            element,
+        }
+    }
+
+    #[inline]
+    pub fn element(self) -> T {
+        self.element
+    }
+
+    #[inline]
+    pub fn simplify(self) -> CapacityError {
+        CapacityError::new(())
+    }
+}
+
+impl<T> fmt::Debug for CapacityError<T> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.write_str("CapacityError")
+    }
+}
+
+impl<T> fmt::Display for CapacityError<T> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.write_str("insufficient capacity")
+    }
+}
+
+impl<T> Error for CapacityError<T> { }
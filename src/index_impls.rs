@@ -1,4 +1,4 @@
-use std::ops::{Index, IndexMut, Range, RangeFrom, RangeTo, RangeFull};
+use core::ops::{Index, IndexMut, Range, RangeFrom, RangeTo, RangeFull};
 use crate::array_vec::ArrayVec;
 use crate::small_vec::{SmallVec, Spilled};
 use coalesce::coalesce;
@@ -6,20 +6,6 @@ use crate::array::Array;
 
 macro_rules! index_impl {
     ($t:ty) => {
-        impl<T: Array> Index<$t> for ArrayVec<T> where [T::Item]: Index<$t> {
-            type Output = <[T::Item] as Index<$t>>::Output;
-
-            fn index(&self, index: $t) -> &Self::Output {
-                Index::index(&**self, index)
-            }
-        }
-
-        impl<T: Array> IndexMut<$t> for ArrayVec<T> {
-            fn index_mut(&mut self, index: $t) -> &mut Self::Output {
-                IndexMut::index_mut(&mut **self, index)
-            }
-        }
-
         impl<T: Array, S: Spilled<ArrayVec<T>> + Index<$t>> Index<$t> for SmallVec<T, S> where ArrayVec<T>: Index<$t, Output=<S as Index<$t>>::Output> {
             type Output = <S as Index<$t>>::Output;
 
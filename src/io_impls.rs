@@ -0,0 +1,49 @@
+use core::cmp::min;
+use core::ptr::copy_nonoverlapping;
+use std::io;
+use crate::{Array, ArrayVec, SmallVec, Spilled, Vector};
+use crate::util::PointerExt;
+
+// Appends as much of `buf` as fits in the spare capacity.
+fn write_vector<V: Vector<Item=u8>>(vec: &mut V, buf: &[u8]) -> usize {
+    let len = vec.len();
+    let count = min(buf.len(), vec.capacity() - len);
+    unsafe {
+        copy_nonoverlapping(buf.as_ptr(), vec.as_mut_ptr().uoffset(len), count);
+        vec.set_len(len + count);
+    }
+    count
+}
+
+/// Writes stop short once the array is full, so `write_all` fails with
+/// `ErrorKind::WriteZero` after filling it.
+impl<A: Array<Item=u8>> io::Write for ArrayVec<A> {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        Ok(write_vector(self, buf))
+    }
+
+    #[inline]
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Writes spill to the heap rather than stopping short.
+impl<A: Array<Item=u8>, S: Spilled<ArrayVec<A>>> io::Write for SmallVec<A, S> where SmallVec<A, S>: Vector<Item=u8> {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.reserve(buf.len());
+        Ok(write_vector(self, buf))
+    }
+
+    #[inline]
+    fn write_all(&mut self, buf: &[u8]) -> io::Result<()> {
+        self.write(buf).map(drop)
+    }
+
+    #[inline]
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
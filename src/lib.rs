@@ -1,14 +1,70 @@
-#![cfg_attr(feature = "unstable", feature(unsize))]
+#![no_std]
+#![cfg_attr(feature = "unstable", feature(unsize, generic_const_exprs))]
+#![cfg_attr(feature = "unstable", allow(incomplete_features))]
+#![cfg_attr(all(feature = "unstable", feature = "alloc"), feature(allocator_api))]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
 
 mod util;
+mod error;
 mod array;
 mod vector;
+mod drain;
 mod array_vec;
+mod array_string;
+mod array_deque;
+mod coerce;
 mod small_dst;
+#[cfg(feature = "alloc")]
+mod small_box;
+mod dst_stack;
+#[cfg(feature = "alloc")]
 mod small_vec;
+#[cfg(feature = "alloc")]
+mod small_string;
+#[cfg(feature = "alloc")]
+mod small_deque;
+mod vector_map;
+mod vector_set;
+mod vector_heap;
+mod bit_vector;
+#[cfg(feature = "serde")]
+mod serde_impls;
+#[cfg(feature = "std")]
+mod io_impls;
 
 pub use array::{Array, ArrayIndex, Addressable};
+pub use error::CapacityError;
 pub use vector::Vector;
-pub use small_vec::{SmallVec, Spilled};
+#[cfg(all(feature = "unstable", feature = "alloc"))]
+pub use vector::VectorAllocator;
+pub use drain::{Drain, Splice};
+#[cfg(feature = "alloc")]
+pub use small_vec::{SmallVec, Spilled, Unspilled};
+#[cfg(feature = "alloc")]
+pub use small_string::SmallString;
+#[cfg(feature = "alloc")]
+pub use small_deque::SmallDeque;
+pub use vector_map::{VectorMap, ArrayMap, Entry, VacantEntry, OccupiedEntry};
+pub use vector_set::{VectorSet, ArraySet};
+pub use bit_vector::{BitVector, BitArrayVec};
+pub use vector_heap::{VectorHeap, ArrayBinaryHeap, HeapOrder, Max, Min, PeekMut};
+#[cfg(feature = "alloc")]
+pub use vector_map::SmallMap;
+#[cfg(feature = "alloc")]
+pub use vector_set::SmallSet;
+#[cfg(feature = "alloc")]
+pub use bit_vector::SmallBitVec;
+#[cfg(feature = "alloc")]
+pub use vector_heap::SmallBinaryHeap;
 pub use array_vec::ArrayVec;
+pub use array_string::ArrayString;
+pub use array_deque::ArrayDeque;
+pub use coerce::Coerce;
 pub use small_dst::SmallDST;
+#[cfg(feature = "alloc")]
+pub use small_box::SmallBox;
+pub use dst_stack::DstStack;
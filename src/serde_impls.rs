@@ -0,0 +1,79 @@
+use core::fmt;
+use core::marker::PhantomData;
+use serde::ser::{Serialize, Serializer};
+use serde::de::{self, Deserialize, Deserializer, SeqAccess, Visitor};
+use crate::{Array, ArrayVec, Vector, CapacityError};
+#[cfg(feature = "alloc")]
+use crate::{SmallVec, Spilled};
+
+// Caps preallocation from an untrusted length hint.
+#[cfg(feature = "alloc")]
+const MAX_PREALLOC: usize = 4096;
+
+impl<A: Array> Serialize for ArrayVec<A> where A::Item: Serialize {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.iter())
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: Array, S: Spilled<ArrayVec<T>>> Serialize for SmallVec<T, S> where SmallVec<T, S>: Vector<Item=T::Item>, T::Item: Serialize {
+    fn serialize<Ser: Serializer>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error> {
+        serializer.collect_seq(self.as_slice())
+    }
+}
+
+struct ArrayVecVisitor<A>(PhantomData<A>);
+
+impl<'de, A: Array> Visitor<'de> for ArrayVecVisitor<A> where A::Item: Deserialize<'de> {
+    type Value = ArrayVec<A>;
+
+    fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "a sequence of at most {} elements", A::len())
+    }
+
+    fn visit_seq<V: SeqAccess<'de>>(self, mut seq: V) -> Result<Self::Value, V::Error> {
+        if seq.size_hint().is_some_and(|len| len > A::len()) {
+            return Err(de::Error::custom(CapacityError::new(())))
+        }
+
+        let mut vec = ArrayVec::new();
+        while let Some(v) = seq.next_element()? {
+            vec.try_push(v).map_err(|e| de::Error::custom(e.simplify()))?;
+        }
+        Ok(vec)
+    }
+}
+
+impl<'de, A: Array> Deserialize<'de> for ArrayVec<A> where A::Item: Deserialize<'de> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_seq(ArrayVecVisitor(PhantomData))
+    }
+}
+
+#[cfg(feature = "alloc")]
+struct SmallVecVisitor<T, S>(PhantomData<(T, S)>);
+
+#[cfg(feature = "alloc")]
+impl<'de, T: Array, S: Spilled<ArrayVec<T>>> Visitor<'de> for SmallVecVisitor<T, S> where SmallVec<T, S>: Vector<Item=T::Item>, T::Item: Deserialize<'de> {
+    type Value = SmallVec<T, S>;
+
+    fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.write_str("a sequence of elements")
+    }
+
+    fn visit_seq<V: SeqAccess<'de>>(self, mut seq: V) -> Result<Self::Value, V::Error> {
+        let mut vec = SmallVec::with_capacity(seq.size_hint().unwrap_or(0).min(MAX_PREALLOC));
+        while let Some(v) = seq.next_element()? {
+            vec.push(v);
+        }
+        Ok(vec)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'de, T: Array, S: Spilled<ArrayVec<T>>> Deserialize<'de> for SmallVec<T, S> where SmallVec<T, S>: Vector<Item=T::Item>, T::Item: Deserialize<'de> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_seq(SmallVecVisitor(PhantomData))
+    }
+}
@@ -0,0 +1,106 @@
+use core::fmt;
+use core::ops::{Deref, DerefMut};
+use core::ptr::read;
+use core::mem::{ManuallyDrop, size_of, size_of_val, align_of, align_of_val};
+use alloc::boxed::Box;
+use coalesce::{Coalesce2, coalesce};
+use crate::small_dst::SmallDST;
+use crate::{ArrayVec, Vector, Coerce};
+
+#[macro_export]
+macro_rules! small_box {
+    ($x:expr) => {
+        {
+            let v = ::core::mem::ManuallyDrop::new($x);
+            unsafe { $crate::SmallBox::new_ref(&*v as &_, &*v) }
+        }
+    };
+}
+
+pub struct SmallBox<T: ?Sized, A: Vector = ArrayVec<[usize; 9]>>(Coalesce2<SmallDST<T, A>, Box<T>>);
+
+impl<T: ?Sized, A: Vector> SmallBox<T, A> {
+    pub fn new<U: Coerce<T>>(v: U) -> Self {
+        let v = ManuallyDrop::new(v);
+        unsafe { Self::new_ref(Coerce::<T>::coerce(&*v), &*v) }
+    }
+
+    /// # Safety
+    ///
+    /// `v` must be a reference to `u` unsized to `T`, and `u` must not be
+    /// dropped afterwards as ownership moves into the returned value.
+    pub unsafe fn new_ref<U>(v: &T, u: &U) -> Self {
+        if SmallDST::<T, A>::fits::<U>() {
+            return SmallBox(Coalesce2::A(SmallDST::new_ref(v, u)))
+        }
+
+        let t = v as *const T;
+        assert_eq!(t as *const U, u as *const U);
+        assert_eq!(size_of_val(v), size_of::<U>());
+        assert_eq!(align_of_val(v), align_of::<U>());
+
+        let ptr = Box::into_raw(Box::new(read(u)));
+        let mut raw = t as *mut T;
+        *(&mut raw as *mut *mut T as *mut *mut u8) = ptr as *mut u8;
+        SmallBox(Coalesce2::B(Box::from_raw(raw)))
+    }
+
+    #[inline]
+    pub fn is_inline(&self) -> bool {
+        match self.0.as_ref() {
+            Coalesce2::A(..) => true,
+            Coalesce2::B(..) => false,
+        }
+    }
+
+    #[inline]
+    pub fn into_inner(self) -> Coalesce2<SmallDST<T, A>, Box<T>> {
+        self.0
+    }
+
+    #[inline]
+    pub fn into_box(self) -> Box<T> {
+        match self.0 {
+            Coalesce2::A(v) => v.into_box(),
+            Coalesce2::B(v) => v,
+        }
+    }
+}
+
+impl<T: ?Sized, A: Vector> From<Box<T>> for SmallBox<T, A> {
+    #[inline]
+    fn from(b: Box<T>) -> Self {
+        SmallBox(Coalesce2::B(b))
+    }
+}
+
+impl<T: ?Sized, A: Vector> From<SmallDST<T, A>> for SmallBox<T, A> {
+    #[inline]
+    fn from(v: SmallDST<T, A>) -> Self {
+        SmallBox(Coalesce2::A(v))
+    }
+}
+
+impl<T: ?Sized, A: Vector> Deref for SmallBox<T, A> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        let v = self.0.as_ref();
+        coalesce!(2 => |v| &**v)
+    }
+}
+
+impl<T: ?Sized, A: Vector> DerefMut for SmallBox<T, A> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut T {
+        let v = self.0.as_mut();
+        coalesce!(2 => |v| &mut **v)
+    }
+}
+
+impl<T: ?Sized + fmt::Debug, A: Vector> fmt::Debug for SmallBox<T, A> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(&**self, fmt)
+    }
+}
@@ -0,0 +1,384 @@
+use core::fmt;
+use core::mem::replace;
+use core::ops::{Index, IndexMut, RangeBounds};
+use core::iter::{FromIterator, FusedIterator};
+use core::hash::{Hash, Hasher};
+use core::hint::unreachable_unchecked;
+use alloc::collections::{VecDeque, vec_deque};
+use coalesce::{Coalesce2, coalesce};
+use crate::array_deque::{ArrayDeque, ArrayDequeIter, ArrayDequeIterMut, ArrayDequeIntoIter, ArrayDequeDrain};
+use crate::small_vec::{Spilled, Unspilled};
+use crate::array::Array;
+
+impl<A: Array> Spilled<ArrayDeque<A>> for VecDeque<A::Item> {
+    type Alloc = ();
+
+    fn spill(v: ArrayDeque<A>, _: &()) -> Self {
+        let mut s = Self::with_capacity(v.len());
+        s.extend(v);
+        s
+    }
+}
+
+impl<A: Array> Unspilled<ArrayDeque<A>> for VecDeque<A::Item> {
+    fn unspill(self) -> Result<ArrayDeque<A>, Self> {
+        if self.len() > A::len() {
+            Err(self)
+        } else {
+            Ok(self.into_iter().collect())
+        }
+    }
+}
+
+pub struct SmallDeque<A: Array>(Coalesce2<ArrayDeque<A>, VecDeque<A::Item>>);
+
+impl<A: Array> SmallDeque<A> {
+    #[inline]
+    pub fn new() -> Self {
+        SmallDeque(Coalesce2::A(ArrayDeque::new()))
+    }
+
+    #[inline]
+    pub fn with_capacity(cap: usize) -> Self {
+        SmallDeque(if cap > A::len() {
+            Coalesce2::B(VecDeque::with_capacity(cap))
+        } else {
+            Coalesce2::A(ArrayDeque::new())
+        })
+    }
+
+    #[inline]
+    pub fn spill(&mut self) {
+        if !self.is_spilled() {
+            match replace(&mut self.0, Coalesce2::B(VecDeque::new())) {
+                Coalesce2::A(v) => { self.0 = Coalesce2::B(VecDeque::spill(v, &())); },
+                _ => unsafe { unreachable_unchecked() },
+            }
+        }
+    }
+
+    pub fn unspill(&mut self) -> bool {
+        if let Coalesce2::B(v) = self.0.as_ref() {
+            if v.len() > A::len() {
+                return false
+            }
+
+            match replace(&mut self.0, Coalesce2::A(ArrayDeque::new())) {
+                Coalesce2::B(v) => match v.unspill() {
+                    Ok(v) => self.0 = Coalesce2::A(v),
+                    Err(v) => {
+                        self.0 = Coalesce2::B(v);
+                        return false
+                    },
+                },
+                _ => unsafe { unreachable_unchecked() },
+            }
+        }
+
+        true
+    }
+
+    #[inline]
+    pub fn is_spilled(&self) -> bool {
+        match self.0.as_ref() {
+            Coalesce2::A(..) => false,
+            Coalesce2::B(..) => true,
+        }
+    }
+
+    #[inline]
+    pub fn into_inner(self) -> Coalesce2<ArrayDeque<A>, VecDeque<A::Item>> {
+        self.0
+    }
+
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        let v = self.0.as_ref();
+        coalesce!(2 => |v| v.capacity())
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        let v = self.0.as_ref();
+        coalesce!(2 => |v| v.len())
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn reserve(&mut self, additional: usize) {
+        if self.capacity() - self.len() < additional {
+            self.spill();
+        }
+
+        if let Coalesce2::B(v) = self.0.as_mut() {
+            v.reserve(additional)
+        }
+    }
+
+    pub fn shrink_to_fit(&mut self) {
+        if !self.unspill() {
+            if let Coalesce2::B(v) = self.0.as_mut() {
+                v.shrink_to_fit()
+            }
+        }
+    }
+
+    #[inline]
+    pub fn push_back(&mut self, value: A::Item) {
+        self.reserve(1);
+        let v = self.0.as_mut();
+        coalesce!(2 => |v| v.push_back(value))
+    }
+
+    #[inline]
+    pub fn push_front(&mut self, value: A::Item) {
+        self.reserve(1);
+        let v = self.0.as_mut();
+        coalesce!(2 => |v| v.push_front(value))
+    }
+
+    #[inline]
+    pub fn pop_front(&mut self) -> Option<A::Item> {
+        let v = self.0.as_mut();
+        coalesce!(2 => |v| v.pop_front())
+    }
+
+    #[inline]
+    pub fn pop_back(&mut self) -> Option<A::Item> {
+        let v = self.0.as_mut();
+        coalesce!(2 => |v| v.pop_back())
+    }
+
+    #[inline]
+    pub fn get(&self, index: usize) -> Option<&A::Item> {
+        let v = self.0.as_ref();
+        coalesce!(2 => |v| v.get(index))
+    }
+
+    #[inline]
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut A::Item> {
+        let v = self.0.as_mut();
+        coalesce!(2 => |v| v.get_mut(index))
+    }
+
+    #[inline]
+    pub fn front(&self) -> Option<&A::Item> {
+        let v = self.0.as_ref();
+        coalesce!(2 => |v| v.front())
+    }
+
+    #[inline]
+    pub fn front_mut(&mut self) -> Option<&mut A::Item> {
+        let v = self.0.as_mut();
+        coalesce!(2 => |v| v.front_mut())
+    }
+
+    #[inline]
+    pub fn back(&self) -> Option<&A::Item> {
+        let v = self.0.as_ref();
+        coalesce!(2 => |v| v.back())
+    }
+
+    #[inline]
+    pub fn back_mut(&mut self) -> Option<&mut A::Item> {
+        let v = self.0.as_mut();
+        coalesce!(2 => |v| v.back_mut())
+    }
+
+    #[inline]
+    pub fn as_slices(&self) -> (&[A::Item], &[A::Item]) {
+        let v = self.0.as_ref();
+        coalesce!(2 => |v| v.as_slices())
+    }
+
+    #[inline]
+    pub fn as_mut_slices(&mut self) -> (&mut [A::Item], &mut [A::Item]) {
+        let v = self.0.as_mut();
+        coalesce!(2 => |v| v.as_mut_slices())
+    }
+
+    #[inline]
+    pub fn make_contiguous(&mut self) -> &mut [A::Item] {
+        let v = self.0.as_mut();
+        coalesce!(2 => |v| v.make_contiguous())
+    }
+
+    #[inline]
+    pub fn clear(&mut self) {
+        let v = self.0.as_mut();
+        coalesce!(2 => |v| v.clear())
+    }
+
+    #[inline]
+    pub fn iter(&self) -> SmallDequeIter<ArrayDequeIter<'_, A::Item>, vec_deque::Iter<'_, A::Item>> {
+        SmallDequeIter(match self.0.as_ref() {
+            Coalesce2::A(v) => Coalesce2::A(v.iter()),
+            Coalesce2::B(v) => Coalesce2::B(v.iter()),
+        })
+    }
+
+    #[inline]
+    pub fn iter_mut(&mut self) -> SmallDequeIter<ArrayDequeIterMut<'_, A::Item>, vec_deque::IterMut<'_, A::Item>> {
+        SmallDequeIter(match self.0.as_mut() {
+            Coalesce2::A(v) => Coalesce2::A(v.iter_mut()),
+            Coalesce2::B(v) => Coalesce2::B(v.iter_mut()),
+        })
+    }
+
+    #[inline]
+    pub fn drain<R: RangeBounds<usize>>(&mut self, range: R) -> SmallDequeIter<ArrayDequeDrain<'_, A>, vec_deque::Drain<'_, A::Item>> {
+        SmallDequeIter(match self.0.as_mut() {
+            Coalesce2::A(v) => Coalesce2::A(v.drain(range)),
+            Coalesce2::B(v) => Coalesce2::B(v.drain(range)),
+        })
+    }
+}
+
+pub struct SmallDequeIter<T1, T2>(Coalesce2<T1, T2>);
+
+impl<T1: Iterator, T2: Iterator<Item=T1::Item>> Iterator for SmallDequeIter<T1, T2> {
+    type Item = T1::Item;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let v = self.0.as_mut();
+        coalesce!(2 => |v| v.next())
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let v = self.0.as_ref();
+        coalesce!(2 => |v| v.size_hint())
+    }
+}
+
+impl<T1: DoubleEndedIterator, T2: DoubleEndedIterator<Item=T1::Item>> DoubleEndedIterator for SmallDequeIter<T1, T2> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let v = self.0.as_mut();
+        coalesce!(2 => |v| v.next_back())
+    }
+}
+
+impl<T1: ExactSizeIterator, T2: ExactSizeIterator<Item=T1::Item>> ExactSizeIterator for SmallDequeIter<T1, T2> { }
+
+impl<T1: FusedIterator, T2: FusedIterator<Item=T1::Item>> FusedIterator for SmallDequeIter<T1, T2> { }
+
+impl<A: Array> IntoIterator for SmallDeque<A> {
+    type Item = A::Item;
+    type IntoIter = SmallDequeIter<ArrayDequeIntoIter<A>, vec_deque::IntoIter<A::Item>>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        SmallDequeIter(match self.0 {
+            Coalesce2::A(v) => Coalesce2::A(v.into_iter()),
+            Coalesce2::B(v) => Coalesce2::B(v.into_iter()),
+        })
+    }
+}
+
+impl<'a, A: Array> IntoIterator for &'a SmallDeque<A> {
+    type Item = &'a A::Item;
+    type IntoIter = SmallDequeIter<ArrayDequeIter<'a, A::Item>, vec_deque::Iter<'a, A::Item>>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, A: Array> IntoIterator for &'a mut SmallDeque<A> {
+    type Item = &'a mut A::Item;
+    type IntoIter = SmallDequeIter<ArrayDequeIterMut<'a, A::Item>, vec_deque::IterMut<'a, A::Item>>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+impl<A: Array> Extend<A::Item> for SmallDeque<A> {
+    fn extend<I: IntoIterator<Item=A::Item>>(&mut self, iter: I) {
+        let iter = iter.into_iter();
+        self.reserve(iter.size_hint().0);
+        for v in iter {
+            self.push_back(v);
+        }
+    }
+}
+
+impl<A: Array> FromIterator<A::Item> for SmallDeque<A> {
+    fn from_iter<I: IntoIterator<Item=A::Item>>(iter: I) -> Self {
+        let mut s = Self::new();
+        s.extend(iter);
+        s
+    }
+}
+
+impl<A: Array> From<ArrayDeque<A>> for SmallDeque<A> {
+    fn from(v: ArrayDeque<A>) -> Self {
+        SmallDeque(Coalesce2::A(v))
+    }
+}
+
+impl<A: Array> From<VecDeque<A::Item>> for SmallDeque<A> {
+    fn from(v: VecDeque<A::Item>) -> Self {
+        SmallDeque(Coalesce2::B(v))
+    }
+}
+
+impl<A: Array> Index<usize> for SmallDeque<A> {
+    type Output = A::Item;
+
+    #[inline]
+    fn index(&self, index: usize) -> &A::Item {
+        let v = self.0.as_ref();
+        coalesce!(2 => |v| &v[index])
+    }
+}
+
+impl<A: Array> IndexMut<usize> for SmallDeque<A> {
+    #[inline]
+    fn index_mut(&mut self, index: usize) -> &mut A::Item {
+        let v = self.0.as_mut();
+        coalesce!(2 => |v| &mut v[index])
+    }
+}
+
+impl<A: Array> Clone for SmallDeque<A> where A::Item: Clone {
+    fn clone(&self) -> Self {
+        SmallDeque(self.0.clone())
+    }
+}
+
+impl<A: Array> Default for SmallDeque<A> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<A: Array> fmt::Debug for SmallDeque<A> where A::Item: fmt::Debug {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<A: Array> Hash for SmallDeque<A> where A::Item: Hash {
+    fn hash<H: Hasher>(&self, h: &mut H) {
+        self.len().hash(h);
+        for v in self {
+            v.hash(h);
+        }
+    }
+}
+
+impl<A: Array, B: Array> PartialEq<SmallDeque<B>> for SmallDeque<A> where A::Item: PartialEq<B::Item> {
+    fn eq(&self, other: &SmallDeque<B>) -> bool {
+        self.len() == other.len() && self.iter().zip(other).all(|(a, b)| a == b)
+    }
+}
+
+impl<A: Array> Eq for SmallDeque<A> where A::Item: Eq { }
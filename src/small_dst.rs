@@ -1,35 +1,47 @@
-use std::fmt;
-use std::ops::{Deref, DerefMut};
-use std::ptr::copy_nonoverlapping;
-use std::mem::{MaybeUninit, forget, size_of, align_of};
-use std::slice::{from_raw_parts, from_raw_parts_mut};
-use std::marker;
-use crate::{ArrayVec, Vector};
+use core::fmt;
+use core::ops::{Deref, DerefMut};
+use core::ptr::{copy_nonoverlapping, read, write, read_unaligned, write_unaligned};
+use core::any::Any;
+use core::mem::{MaybeUninit, ManuallyDrop, forget, size_of, size_of_val, align_of, align_of_val};
+use core::slice::from_raw_parts;
+use core::alloc::Layout;
+#[cfg(feature = "alloc")]
+use alloc::{boxed::Box, alloc::{alloc, handle_alloc_error}};
+use core::marker;
+#[cfg(all(feature = "unstable", feature = "alloc"))]
+use core::{ptr::NonNull, alloc::{Allocator, AllocError}};
+use crate::{ArrayVec, Vector, Coerce};
+use crate::coerce::CallOnceShim;
 
 #[macro_export]
 macro_rules! small_dst {
     ($x:expr) => {
         {
-            let v = $x;
-            let dst = unsafe { $crate::SmallDST::new_ref(&v as &_, &v) };
-            ::std::mem::forget(v);
-            dst
+            let v = ::core::mem::ManuallyDrop::new($x);
+            unsafe { $crate::SmallDST::new_ref(&*v as &_, &*v) }
         }
     };
 }
 
-pub struct SmallDST<T: ?Sized, A: Vector<Item=usize> = ArrayVec<[usize; 9]>> {
+struct AlignCheck<U, W>(marker::PhantomData<(U, W)>);
+
+impl<U, W> AlignCheck<U, W> {
+    const OK: () = assert!(align_of::<U>() <= align_of::<W>(), "value is over-aligned for the SmallDST storage word");
+}
+
+pub struct SmallDST<T: ?Sized, A: Vector = ArrayVec<[usize; 9]>> {
     data: A,
     _phantom: marker::PhantomData<T>,
 }
 
-impl<T: ?Sized + Clone, A: Vector<Item=usize>> Clone for SmallDST<T, A> {
+#[allow(clippy::needless_maybe_sized, clippy::needless_borrow)]
+impl<T: ?Sized + Clone, A: Vector> Clone for SmallDST<T, A> {
     fn clone(&self) -> Self {
         small_dst!((&**self).clone())
     }
 }
 
-impl<T: ?Sized, A: Vector<Item=usize>> SmallDST<T, A> {
+impl<T: ?Sized, A: Vector> SmallDST<T, A> {
     fn ptr_len() -> usize {
         assert_eq!(size_of::<*const T>() % size_of::<usize>(), 0);
         size_of::<*const T>() / size_of::<usize>()
@@ -39,34 +51,60 @@ impl<T: ?Sized, A: Vector<Item=usize>> SmallDST<T, A> {
         Self::ptr_len() - 1
     }
 
-    fn ref_data<'a>(v: &'a *const T) -> &'a [usize] {
+    fn ref_data(v: &*const T) -> &[usize] {
         unsafe { from_raw_parts(v as *const *const T as *const usize, Self::ptr_len()) }
     }
 
-    fn mut_data_const<'a>(v: &'a mut MaybeUninit<*const T>) -> &'a mut [MaybeUninit<usize>] {
-        unsafe { from_raw_parts_mut(v.as_mut_ptr() as *mut usize as *mut MaybeUninit<usize>, Self::ptr_len()) }
+    fn read_meta<P>(&self, v: &mut MaybeUninit<P>) {
+        unsafe { copy_nonoverlapping(self.data.as_ptr() as *const u8, (v.as_mut_ptr() as *mut usize).add(1) as *mut u8, Self::data_len() * size_of::<usize>()) }
     }
 
-    fn mut_data_mut<'a>(v: &'a mut MaybeUninit<*mut T>) -> &'a mut [MaybeUninit<usize>] {
-        unsafe { from_raw_parts_mut(v.as_mut_ptr() as *mut usize as *mut MaybeUninit<usize>, Self::ptr_len()) }
+    // The data address is written as a pointer so that it keeps the
+    // provenance of the storage.
+    unsafe fn write_addr<P>(mut v: MaybeUninit<P>, value: *mut u8) -> P {
+        write(v.as_mut_ptr() as *mut *mut u8, value);
+        v.assume_init()
     }
 
-    #[cfg(feature = "unstable")]
-    pub fn new<U: marker::Unsize<T>>(v: U) -> Self {
-        let ret = unsafe { Self::new_ref(&v, &v) };
-        forget(v);
+    // Derived from the mutable storage pointer, so it stays valid after
+    // `set_len` gives up ownership of the value.
+    fn value_mut_ptr(&mut self) -> *mut T {
+        let mut v = MaybeUninit::<*mut T>::uninit();
+        self.read_meta(&mut v);
+        unsafe { Self::write_addr(v, self.data.as_mut_ptr().add(Self::header_len()) as *mut u8) }
+    }
+
+    pub fn new<U: Coerce<T>>(v: U) -> Self {
+        #[allow(clippy::let_unit_value)]
+        let () = AlignCheck::<U, A::Item>::OK;
+
+        let v = ManuallyDrop::new(v);
+        let mut ret = unsafe { Self::new_ref(Coerce::<T>::coerce(&*v), &*v) };
+        if let Some(shim) = <U as Coerce<T>>::call_once_shim() {
+            ret.push_call_once_shim(shim);
+        }
         ret
     }
 
+    /// # Safety
+    ///
+    /// `v` must be a reference to `u` unsized to `T`, and `u` must not be
+    /// dropped afterwards as ownership moves into the returned value.
     #[inline]
     pub unsafe fn new_ref<U>(v: &T, u: &U) -> Self {
         let t = v as *const T;
         let data = Self::ref_data(&t);
         assert_eq!(data[0], u as *const U as usize);
+        assert_eq!(size_of_val(v), size_of::<U>());
+        assert_eq!(align_of_val(v), align_of::<U>());
 
         Self::from_ref(u, &data[1..])
     }
 
+    /// # Safety
+    ///
+    /// The storage holds pointer metadata and raw value bytes, followed by
+    /// a call shim for closures stored with `new`; it must not be modified.
     #[inline]
     pub unsafe fn data(&self) -> &A {
         &self.data
@@ -78,16 +116,114 @@ impl<T: ?Sized, A: Vector<Item=usize>> SmallDST<T, A> {
         ret
     }
 
+    fn header_len() -> usize {
+        (Self::data_len() * size_of::<usize>()).div_ceil(size_of::<A::Item>())
+    }
+
+    #[cfg(feature = "alloc")]
+    fn value_len<U>() -> usize {
+        size_of::<U>().div_ceil(size_of::<A::Item>())
+    }
+
+    fn value_ptr(&self) -> *const u8 {
+        unsafe { self.data.as_ptr().add(Self::header_len()) as *const u8 }
+    }
+
+    fn shim_len() -> usize {
+        size_of::<CallOnceShim>().div_ceil(size_of::<A::Item>())
+    }
+
+    // The shim is stored after the value when there is room for it, so the
+    // values built by `new_ref` or `from_raw` are left without one.
+    fn push_call_once_shim(&mut self, shim: CallOnceShim) {
+        let len = self.data.len();
+        if self.data.capacity() - len >= Self::shim_len() {
+            unsafe {
+                self.data.set_len(len + Self::shim_len());
+                write_unaligned(self.data.as_mut_ptr().add(len) as *mut CallOnceShim, shim);
+            }
+        }
+    }
+
+    fn call_once_shim(&self) -> Option<CallOnceShim> {
+        let offset = Self::header_len() + size_of_val::<T>(self).div_ceil(size_of::<A::Item>());
+        if self.data.len() >= offset + Self::shim_len() {
+            unsafe { Some(read_unaligned(self.data.as_ptr().add(offset) as *const CallOnceShim)) }
+        } else {
+            None
+        }
+    }
+
+    /// # Safety
+    ///
+    /// `shim` must have been recorded for the stored value and `args` must
+    /// be its argument tuple.
+    unsafe fn call_in_place<R, Args>(mut self, shim: CallOnceShim, args: Args) -> R {
+        let mut args = ManuallyDrop::new(args);
+        let mut ret = MaybeUninit::<R>::uninit();
+        self.data.set_len(0);
+        let f = self.data.as_mut_ptr().add(Self::header_len()) as *mut u8;
+        shim(f, &mut *args as *mut Args as *mut u8, ret.as_mut_ptr() as *mut u8);
+        ret.assume_init()
+    }
+
+    #[cfg(feature = "alloc")]
+    pub(crate) fn fits<U>() -> bool {
+        align_of::<U>() <= align_of::<A::Item>() && Self::header_len() + Self::value_len::<U>() <= A::new().capacity()
+    }
+
+    #[cfg(feature = "alloc")]
+    pub(crate) fn into_box(mut self) -> Box<T> {
+        unsafe {
+            let layout = Layout::for_value::<T>(&*self);
+            let ptr = if layout.size() == 0 {
+                layout.align() as *mut u8
+            } else {
+                let ptr = alloc(layout);
+                if ptr.is_null() {
+                    handle_alloc_error(layout)
+                }
+                ptr
+            };
+
+            let mut raw = &mut *self as *mut T;
+            copy_nonoverlapping(raw as *const u8, ptr, layout.size());
+            *(&mut raw as *mut *mut T as *mut *mut u8) = ptr;
+            self.data.set_len(0);
+            Box::from_raw(raw)
+        }
+    }
+
+    // Closures without a recorded call shim, such as those from `small_dst!`,
+    // are moved to the heap to be called by value.
+    #[cfg(all(feature = "alloc", not(feature = "unstable")))]
+    fn call_once_with<R, F: FnOnce(Box<T>) -> R>(self, f: F) -> R {
+        f(self.into_box())
+    }
+
+    #[cfg(all(feature = "alloc", feature = "unstable"))]
+    fn call_once_with<R, F: FnOnce(Box<T, InPlace>) -> R>(mut self, f: F) -> R {
+        unsafe {
+            self.data.set_len(0);
+            f(Box::from_raw_in(self.value_mut_ptr(), InPlace))
+        }
+    }
+
     unsafe fn from_ref<U>(v: &U, data: &[usize]) -> Self {
-        assert!(align_of::<U>() <= align_of::<A::Item>()); // TODO: support arbitrary alignments
+        Self::from_raw(v as *const U as *const u8, Layout::new::<U>(), data)
+    }
+
+    pub(crate) unsafe fn from_raw(v: *const u8, layout: Layout, data: &[usize]) -> Self {
+        assert!(size_of::<A::Item>() > 0, "SmallDST storage words must not be zero-sized");
+        assert!(layout.align() <= align_of::<A::Item>(), "value is over-aligned for the SmallDST storage word");
         assert_eq!(data.len(), Self::data_len());
 
-        let size = (size_of::<U>() + size_of::<usize>() - 1) / size_of::<usize>();
+        let len = Self::header_len() + layout.size().div_ceil(size_of::<A::Item>());
 
-        let mut a = A::with_capacity(Self::data_len() + size);
-        a.set_len(Self::data_len() + size);
-        copy_nonoverlapping(data.as_ptr(), a.as_mut_ptr(), Self::data_len());
-        copy_nonoverlapping(v as *const U as *const u8, a.as_mut_ptr().offset(Self::data_len() as isize) as *mut u8, size_of::<U>());
+        let mut a = A::with_capacity(len);
+        a.set_len(len);
+        copy_nonoverlapping(data.as_ptr() as *const u8, a.as_mut_ptr() as *mut u8, Self::data_len() * size_of::<usize>());
+        copy_nonoverlapping(v, a.as_mut_ptr().add(Self::header_len()) as *mut u8, layout.size());
 
         SmallDST {
             data: a,
@@ -96,10 +232,10 @@ impl<T: ?Sized, A: Vector<Item=usize>> SmallDST<T, A> {
     }
 }
 
-impl<T: ?Sized, A: Vector<Item=usize>> Drop for SmallDST<T, A> {
+impl<T: ?Sized, A: Vector> Drop for SmallDST<T, A> {
     #[inline]
     fn drop(&mut self) {
-        use std::ptr;
+        use core::ptr;
 
         unsafe {
             if self.data.len() > 0 {
@@ -110,46 +246,137 @@ impl<T: ?Sized, A: Vector<Item=usize>> Drop for SmallDST<T, A> {
     }
 }
 
-impl<T: Sized, A: Vector<Item=usize>> From<T> for SmallDST<T, A> {
+impl<T: Sized, A: Vector> From<T> for SmallDST<T, A> {
     #[inline]
     fn from(t: T) -> Self {
         unsafe { Self::from_value(t, &[]) }
     }
 }
 
-impl<T: ?Sized, A: Vector<Item=usize>> Deref for SmallDST<T, A> {
+// Owns a value in place without managing its memory, so that a boxed
+// `FnOnce` can be called without moving it to the heap.
+#[cfg(all(feature = "unstable", feature = "alloc"))]
+struct InPlace;
+
+#[cfg(all(feature = "unstable", feature = "alloc"))]
+unsafe impl Allocator for InPlace {
+    fn allocate(&self, _: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        Err(AllocError)
+    }
+
+    unsafe fn deallocate(&self, _: NonNull<u8>, _: Layout) { }
+}
+
+macro_rules! call_once_impl {
+    ($($a:ident),*) => {
+        call_once_impl!(@ [$($a),*] dyn FnOnce($($a),*) -> R + 'a);
+        call_once_impl!(@ [$($a),*] dyn FnOnce($($a),*) -> R + Send + 'a);
+        call_once_impl!(@ [$($a),*] dyn FnOnce($($a),*) -> R + Send + Sync + 'a);
+    };
+    (@ [$($a:ident),*] $t:ty) => {
+        impl<'a, R, $($a,)* S: Vector> SmallDST<$t, S> {
+            /// Calls the closure, consuming it.
+            ///
+            /// Closures stored with `SmallDST::new` are called in place. Ones
+            /// stored otherwise, such as through `small_dst!`, are first moved
+            /// into a heap allocation unless the `unstable` feature is enabled,
+            /// and cannot be called without the `alloc` feature.
+            ///
+            /// # Panics
+            ///
+            /// Panics without the `alloc` feature if the closure was not stored
+            /// with `SmallDST::new`.
+            #[allow(non_snake_case)]
+            pub fn call_once(self, $($a: $a),*) -> R {
+                if let Some(shim) = self.call_once_shim() {
+                    return unsafe { self.call_in_place(shim, ($($a,)*)) }
+                }
+
+                #[cfg(feature = "alloc")]
+                {
+                    self.call_once_with(|f| f($($a),*))
+                }
+                #[cfg(not(feature = "alloc"))]
+                {
+                    let _ = ($($a,)*);
+                    panic!("closure was not stored with SmallDST::new")
+                }
+            }
+        }
+    };
+}
+
+call_once_impl!();
+call_once_impl!(A1);
+call_once_impl!(A1, A2);
+call_once_impl!(A1, A2, A3);
+call_once_impl!(A1, A2, A3, A4);
+call_once_impl!(A1, A2, A3, A4, A5);
+call_once_impl!(A1, A2, A3, A4, A5, A6);
+
+macro_rules! downcast_impl {
+    ($t:ty) => {
+        impl<A: Vector> SmallDST<$t, A> {
+            #[inline]
+            pub fn is<U: Any>(&self) -> bool {
+                (**self).is::<U>()
+            }
+
+            pub fn downcast<U: Any>(mut self) -> Result<U, Self> {
+                if self.is::<U>() {
+                    unsafe {
+                        let v = read(&*self as *const $t as *const U);
+                        self.data.set_len(0);
+                        Ok(v)
+                    }
+                } else {
+                    Err(self)
+                }
+            }
+
+            #[inline]
+            pub fn downcast_ref<U: Any>(&self) -> Option<&U> {
+                (**self).downcast_ref()
+            }
+
+            #[inline]
+            pub fn downcast_mut<U: Any>(&mut self) -> Option<&mut U> {
+                (**self).downcast_mut()
+            }
+        }
+    };
+}
+
+downcast_impl!(dyn Any);
+downcast_impl!(dyn Any + Send);
+downcast_impl!(dyn Any + Send + Sync);
+
+impl<'a, A: Vector> From<&'a str> for SmallDST<str, A> {
+    fn from(s: &'a str) -> Self {
+        let t = s as *const str;
+        unsafe { Self::from_raw(s.as_ptr(), Layout::for_value(s), &Self::ref_data(&t)[1..]) }
+    }
+}
+
+impl<T: ?Sized, A: Vector> Deref for SmallDST<T, A> {
     type Target = T;
 
     #[inline]
     fn deref(&self) -> &Self::Target {
-        unsafe {
-            let mut v = MaybeUninit::uninit();
-            {
-                let v = Self::mut_data_const(&mut v);
-                v[0] = MaybeUninit::new(self.data.as_ptr().offset(Self::data_len() as isize) as usize);
-                copy_nonoverlapping(self.data.as_ptr(), v[1..].as_mut_ptr() as *mut usize, Self::data_len());
-            }
-            &*v.assume_init()
-        }
+        let mut v = MaybeUninit::<*const T>::uninit();
+        self.read_meta(&mut v);
+        unsafe { &*Self::write_addr(v, self.value_ptr() as *mut u8) }
     }
 }
 
-impl<T: ?Sized, A: Vector<Item=usize>> DerefMut for SmallDST<T, A> {
+impl<T: ?Sized, A: Vector> DerefMut for SmallDST<T, A> {
     #[inline]
     fn deref_mut(&mut self) -> &mut Self::Target {
-        unsafe {
-            let mut v = MaybeUninit::uninit();
-            {
-                let v = Self::mut_data_mut(&mut v);
-                v[0] = MaybeUninit::new(self.data.as_ptr().offset(Self::data_len() as isize) as usize);
-                copy_nonoverlapping(self.data.as_ptr(), v[1..].as_mut_ptr() as *mut usize, Self::data_len());
-            }
-            &mut *v.assume_init()
-        }
+        unsafe { &mut *self.value_mut_ptr() }
     }
 }
 
-impl<T: ?Sized + fmt::Debug, A: Vector<Item=usize>> fmt::Debug for SmallDST<T, A> {
+impl<T: ?Sized + fmt::Debug, A: Vector> fmt::Debug for SmallDST<T, A> {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
         fmt::Debug::fmt(&**self, fmt)
     }
@@ -0,0 +1,287 @@
+use core::fmt;
+use core::str::FromStr;
+use core::hash::{Hash, Hasher};
+use core::borrow::{Borrow, BorrowMut};
+use core::cmp::Ordering;
+use core::convert::Infallible;
+use core::mem::replace;
+use core::ops::{Deref, DerefMut};
+use core::hint::unreachable_unchecked;
+use alloc::string::String;
+use coalesce::{Coalesce2, coalesce};
+use crate::array_string::ArrayString;
+use crate::small_vec::Spilled;
+use crate::array::Array;
+
+impl<A: Array<Item=u8>> Spilled<ArrayString<A>> for String {
+    type Alloc = ();
+
+    fn spill(v: ArrayString<A>, _: &()) -> Self {
+        let mut s = String::with_capacity(v.len());
+        s.push_str(&v);
+        s
+    }
+}
+
+pub struct SmallString<A: Array<Item=u8>>(Coalesce2<ArrayString<A>, String>);
+
+impl<A: Array<Item=u8>> SmallString<A> {
+    #[inline]
+    pub fn new() -> Self {
+        SmallString(Coalesce2::A(ArrayString::new()))
+    }
+
+    #[inline]
+    pub fn with_capacity(cap: usize) -> Self {
+        SmallString(if cap > A::len() {
+            Coalesce2::B(String::with_capacity(cap))
+        } else {
+            Coalesce2::A(ArrayString::new())
+        })
+    }
+
+    #[inline]
+    pub fn spill(&mut self) {
+        if !self.is_spilled() {
+            match replace(&mut self.0, Coalesce2::B(String::new())) {
+                Coalesce2::A(v) => { self.0 = Coalesce2::B(String::spill(v, &())); },
+                _ => unsafe { unreachable_unchecked() },
+            }
+        }
+    }
+
+    #[inline]
+    pub fn is_spilled(&self) -> bool {
+        match self.0.as_ref() {
+            Coalesce2::A(..) => false,
+            Coalesce2::B(..) => true,
+        }
+    }
+
+    #[inline]
+    pub fn into_inner(self) -> Coalesce2<ArrayString<A>, String> {
+        self.0
+    }
+
+    #[inline]
+    pub fn into_string(self) -> String {
+        match self.0 {
+            Coalesce2::A(v) => String::spill(v, &()),
+            Coalesce2::B(v) => v,
+        }
+    }
+
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        let v = self.0.as_ref();
+        coalesce!(2 => |v| v.capacity())
+    }
+
+    #[inline]
+    pub fn as_str(&self) -> &str {
+        self
+    }
+
+    #[inline]
+    pub fn as_mut_str(&mut self) -> &mut str {
+        self
+    }
+
+    pub fn reserve(&mut self, additional: usize) {
+        if self.capacity() - self.len() < additional {
+            self.spill();
+        }
+
+        if let Coalesce2::B(v) = self.0.as_mut() {
+            v.reserve(additional)
+        }
+    }
+
+    pub fn push_str(&mut self, s: &str) {
+        self.reserve(s.len());
+        let v = self.0.as_mut();
+        coalesce!(2 => |v| v.push_str(s))
+    }
+
+    #[inline]
+    pub fn push(&mut self, c: char) {
+        self.push_str(c.encode_utf8(&mut [0u8; 4]))
+    }
+
+    #[inline]
+    pub fn pop(&mut self) -> Option<char> {
+        let v = self.0.as_mut();
+        coalesce!(2 => |v| v.pop())
+    }
+
+    #[inline]
+    pub fn truncate(&mut self, new_len: usize) {
+        let v = self.0.as_mut();
+        coalesce!(2 => |v| v.truncate(new_len))
+    }
+
+    #[inline]
+    pub fn clear(&mut self) {
+        let v = self.0.as_mut();
+        coalesce!(2 => |v| v.clear())
+    }
+}
+
+impl<A: Array<Item=u8>> Deref for SmallString<A> {
+    type Target = str;
+
+    #[inline]
+    fn deref(&self) -> &str {
+        let v = self.0.as_ref();
+        coalesce!(2 => |v| v.as_str())
+    }
+}
+
+impl<A: Array<Item=u8>> DerefMut for SmallString<A> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut str {
+        let v = self.0.as_mut();
+        coalesce!(2 => |v| v.as_mut_str())
+    }
+}
+
+impl<A: Array<Item=u8>> AsRef<str> for SmallString<A> {
+    #[inline]
+    fn as_ref(&self) -> &str {
+        self
+    }
+}
+
+impl<A: Array<Item=u8>> Borrow<str> for SmallString<A> {
+    #[inline]
+    fn borrow(&self) -> &str {
+        self
+    }
+}
+
+impl<A: Array<Item=u8>> BorrowMut<str> for SmallString<A> {
+    #[inline]
+    fn borrow_mut(&mut self) -> &mut str {
+        self
+    }
+}
+
+impl<A: Array<Item=u8>> fmt::Write for SmallString<A> {
+    #[inline]
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.push_str(s);
+        Ok(())
+    }
+}
+
+impl<'a, A: Array<Item=u8>> From<&'a str> for SmallString<A> {
+    fn from(s: &'a str) -> Self {
+        let mut v = Self::with_capacity(s.len());
+        v.push_str(s);
+        v
+    }
+}
+
+impl<A: Array<Item=u8>> From<String> for SmallString<A> {
+    #[inline]
+    fn from(s: String) -> Self {
+        SmallString(Coalesce2::B(s))
+    }
+}
+
+impl<A: Array<Item=u8>> From<ArrayString<A>> for SmallString<A> {
+    #[inline]
+    fn from(s: ArrayString<A>) -> Self {
+        SmallString(Coalesce2::A(s))
+    }
+}
+
+impl<A: Array<Item=u8>> From<SmallString<A>> for String {
+    #[inline]
+    fn from(s: SmallString<A>) -> Self {
+        s.into_string()
+    }
+}
+
+impl<A: Array<Item=u8>> FromStr for SmallString<A> {
+    type Err = Infallible;
+
+    #[inline]
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(s.into())
+    }
+}
+
+impl<A: Array<Item=u8>> Clone for SmallString<A> {
+    fn clone(&self) -> Self {
+        SmallString(self.0.clone())
+    }
+}
+
+impl<A: Array<Item=u8>> Default for SmallString<A> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<A: Array<Item=u8>> fmt::Debug for SmallString<A> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(&**self, fmt)
+    }
+}
+
+impl<A: Array<Item=u8>> fmt::Display for SmallString<A> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&**self, fmt)
+    }
+}
+
+impl<A: Array<Item=u8>> Hash for SmallString<A> {
+    fn hash<H: Hasher>(&self, h: &mut H) {
+        (**self).hash(h)
+    }
+}
+
+impl<A: Array<Item=u8>, B: Array<Item=u8>> PartialEq<SmallString<B>> for SmallString<A> {
+    fn eq(&self, other: &SmallString<B>) -> bool {
+        **self == **other
+    }
+}
+
+impl<A: Array<Item=u8>> PartialEq<str> for SmallString<A> {
+    fn eq(&self, other: &str) -> bool {
+        **self == *other
+    }
+}
+
+impl<'a, A: Array<Item=u8>> PartialEq<&'a str> for SmallString<A> {
+    fn eq(&self, other: &&'a str) -> bool {
+        **self == **other
+    }
+}
+
+impl<A: Array<Item=u8>> PartialEq<SmallString<A>> for str {
+    fn eq(&self, other: &SmallString<A>) -> bool {
+        *self == **other
+    }
+}
+
+impl<A: Array<Item=u8>> Eq for SmallString<A> { }
+
+impl<A: Array<Item=u8>, B: Array<Item=u8>> PartialOrd<SmallString<B>> for SmallString<A> {
+    fn partial_cmp(&self, other: &SmallString<B>) -> Option<Ordering> {
+        (**self).partial_cmp(&**other)
+    }
+}
+
+impl<A: Array<Item=u8>> PartialOrd<str> for SmallString<A> {
+    fn partial_cmp(&self, other: &str) -> Option<Ordering> {
+        (**self).partial_cmp(other)
+    }
+}
+
+impl<A: Array<Item=u8>> Ord for SmallString<A> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (**self).cmp(&**other)
+    }
+}
@@ -1,10 +1,14 @@
-use std::fmt;
-use std::mem::replace;
-use std::ptr::copy_nonoverlapping;
-use std::ops::{Deref, DerefMut};
-use std::iter::FromIterator;
-use std::hash::{Hash, Hasher};
-use std::hint::unreachable_unchecked;
+use core::fmt;
+use core::mem::{replace, take};
+use core::ptr::copy_nonoverlapping;
+use core::ops::{Deref, DerefMut};
+use core::iter::FromIterator;
+use core::hash::{Hash, Hasher};
+use core::hint::unreachable_unchecked;
+use alloc::vec::Vec;
+use alloc::boxed::Box;
+#[cfg(feature = "unstable")]
+use crate::vector::VectorAllocator;
 use coalesce::{Coalesce2, coalesce};
 use crate::array_vec::ArrayVec;
 use crate::vector::Vector;
@@ -13,40 +17,107 @@ use crate::array::Array;
 #[path = "index_impls.rs"]
 mod index_impls;
 
-pub trait Spilled<T> {
-    fn spill(v: T) -> Self;
+pub trait Spilled<T>: Sized {
+    /// State kept alongside the inline storage to create the spilled vector,
+    /// such as its allocator.
+    type Alloc;
+
+    fn spill(v: T, alloc: &Self::Alloc) -> Self;
 }
 
-impl<T: Array> Spilled<ArrayVec<T>> for Vec<T::Item> {
-    fn spill(mut v: ArrayVec<T>) -> Self {
-        let len = v.len();
+pub trait Unspilled<T>: Sized {
+    fn unspill(self) -> Result<T, Self>;
+}
 
-        let mut s = Self::with_capacity(len);
-        unsafe {
-            v.set_len(0);
-            copy_nonoverlapping(v.as_ptr(), s.as_mut_ptr(), len);
-            s.set_len(len);
+macro_rules! vec_spill_impl {
+    ([$($generics:tt)*] $vec:ty, $alloc:ty, $with_capacity:expr) => {
+        impl<T: Array, $($generics)*> Spilled<ArrayVec<T>> for $vec {
+            type Alloc = $alloc;
+
+            fn spill(mut v: ArrayVec<T>, alloc: &$alloc) -> Self {
+                let len = v.len();
+
+                let mut s: Self = $with_capacity(len, alloc);
+                unsafe {
+                    v.set_len(0);
+                    copy_nonoverlapping(v.as_ptr(), s.as_mut_ptr(), len);
+                    s.set_len(len);
+                }
+                s
+            }
         }
-        s
-    }
+
+        impl<T: Array, $($generics)*> Unspilled<ArrayVec<T>> for $vec {
+            fn unspill(mut self) -> Result<ArrayVec<T>, Self> {
+                let len = self.len();
+                if len > T::len() {
+                    return Err(self)
+                }
+
+                let mut v = ArrayVec::new();
+                unsafe {
+                    self.set_len(0);
+                    copy_nonoverlapping(self.as_ptr(), v.as_mut_ptr(), len);
+                    v.set_len(len);
+                }
+                Ok(v)
+            }
+        }
+    };
 }
 
-pub struct SmallVec<T: Array, S = Vec<<T as Array>::Item>>(Coalesce2<ArrayVec<T>, S>);
+#[cfg(not(feature = "unstable"))]
+vec_spill_impl!([] Vec<T::Item>, (), |len, _| Vec::with_capacity(len));
+
+#[cfg(feature = "unstable")]
+vec_spill_impl!([A: VectorAllocator] Vec<T::Item, A>, A, |len, alloc: &A| Vec::with_capacity_in(len, alloc.clone()));
+
+/// Formats into a byte `SmallVec` with the given inline capacity, like
+/// `format!`.
+#[macro_export]
+macro_rules! format_small {
+    ($n:expr, $($arg:tt)*) => {
+        $crate::SmallVec::<[u8; $n]>::format(::core::format_args!($($arg)*))
+    };
+}
 
-impl<T: Array, S: Clone> Clone for SmallVec<T, S> where ArrayVec<T>: Clone {
+pub struct SmallVec<T: Array, S: Spilled<ArrayVec<T>> = Vec<<T as Array>::Item>>(Coalesce2<ArrayVec<T>, S>, S::Alloc);
+
+impl<T: Array, S: Spilled<ArrayVec<T>> + Clone> Clone for SmallVec<T, S> where ArrayVec<T>: Clone, S::Alloc: Clone {
     fn clone(&self) -> Self {
-        SmallVec(self.0.clone())
+        SmallVec(self.0.clone(), self.1.clone())
     }
 }
 
 impl<T: Array, S: Vector + Spilled<ArrayVec<T>>> SmallVec<T, S> {
+    /// Creates an empty vector that spills using `alloc`.
+    #[inline]
+    pub fn new_in(alloc: S::Alloc) -> Self {
+        SmallVec(Coalesce2::A(ArrayVec::new()), alloc)
+    }
+
+    /// Creates a vector with room for `cap` items, spilling using `alloc`
+    /// if they don't fit inline.
+    pub fn with_capacity_in(cap: usize, alloc: S::Alloc) -> Self {
+        let mut v = Self::new_in(alloc);
+        if cap > T::len() {
+            v.0 = Coalesce2::B(S::spill(ArrayVec::new(), &v.1));
+            v.reserve_exact_spilled(cap);
+        }
+        v
+    }
+
+    fn reserve_exact_spilled(&mut self, additional: usize) {
+        if let Coalesce2::B(v) = self.0.as_mut() {
+            v.reserve_exact(additional);
+        }
+    }
+
     #[inline]
     pub fn spill(&mut self) {
-        if !self.is_spilled() {
-            match replace(&mut self.0, Coalesce2::B(S::new())) {
-                Coalesce2::A(v) => { self.0 = Coalesce2::B(S::spill(v)); },
-                _ => unsafe { unreachable_unchecked() },
-            }
+        if let Coalesce2::A(v) = self.0.as_mut() {
+            let v = take(v);
+            self.0 = Coalesce2::B(S::spill(v, &self.1));
         }
     }
 
@@ -59,29 +130,56 @@ impl<T: Array, S: Vector + Spilled<ArrayVec<T>>> SmallVec<T, S> {
     }
 }
 
-impl<T: Array, S> SmallVec<T, S> {
+impl<T: Array, S: Vector + Spilled<ArrayVec<T>> + Unspilled<ArrayVec<T>>> SmallVec<T, S> {
+    pub fn unspill(&mut self) -> bool {
+        if self.is_spilled() {
+            match replace(&mut self.0, Coalesce2::A(ArrayVec::new())) {
+                Coalesce2::B(v) => match v.unspill() {
+                    Ok(v) => self.0 = Coalesce2::A(v),
+                    Err(v) => {
+                        self.0 = Coalesce2::B(v);
+                        return false
+                    },
+                },
+                _ => unsafe { unreachable_unchecked() },
+            }
+        }
+
+        true
+    }
+
+    /// Moves the items back inline if they fit, and otherwise shrinks the
+    /// spilled vector.
+    ///
+    /// `Vector::shrink_to_fit` only does the latter, and never unspills.
+    pub fn shrink_to_inline(&mut self) {
+        if !self.unspill() {
+            if let Coalesce2::B(v) = self.0.as_mut() {
+                v.shrink_to_fit();
+            }
+        }
+    }
+}
+
+impl<T: Array, S: Spilled<ArrayVec<T>>> SmallVec<T, S> {
     #[inline]
     pub fn into_inner(self) -> Coalesce2<ArrayVec<T>, S> {
         self.0
     }
 }
 
-impl<T: Array, S> From<ArrayVec<T>> for SmallVec<T, S> {
+impl<T: Array, S: Spilled<ArrayVec<T>>> From<ArrayVec<T>> for SmallVec<T, S> where S::Alloc: Default {
     fn from(v: ArrayVec<T>) -> Self {
-        SmallVec(Coalesce2::A(v))
+        SmallVec(Coalesce2::A(v), Default::default())
     }
 }
 
-unsafe impl<T: Array, S: Vector<Item=T::Item> + Spilled<ArrayVec<T>>> Vector for SmallVec<T, S> {
+unsafe impl<T: Array, S: Vector<Item=T::Item> + Spilled<ArrayVec<T>>> Vector for SmallVec<T, S> where S::Alloc: Default {
     type Item = T::Item;
 
     #[inline]
     fn with_capacity(cap: usize) -> Self {
-        SmallVec(if cap > T::len() {
-            Coalesce2::B(S::with_capacity(cap))
-        } else {
-            Coalesce2::A(Default::default())
-        })
+        Self::with_capacity_in(cap, Default::default())
     }
 
     #[inline]
@@ -116,6 +214,9 @@ unsafe impl<T: Array, S: Vector<Item=T::Item> + Spilled<ArrayVec<T>>> Vector for
         coalesce!(2 => |v| v.reserve_exact(additional))
     }
 
+    /// Shrinks the spilled vector, but never moves the items back inline.
+    ///
+    /// Use `SmallVec::shrink_to_inline` to unspill as well.
     #[inline]
     fn shrink_to_fit(&mut self) {
         let v = self.0.as_mut();
@@ -128,6 +229,12 @@ unsafe impl<T: Array, S: Vector<Item=T::Item> + Spilled<ArrayVec<T>>> Vector for
         coalesce!(2 => |v| v.into_boxed_slice())
     }
 
+    #[inline]
+    fn into_vec(self) -> Vec<T::Item> {
+        let v = self.into_inner();
+        coalesce!(2 => |v| v.into_vec())
+    }
+
     #[inline]
     unsafe fn set_len(&mut self, len: usize) {
         let v = self.0.as_mut();
@@ -153,7 +260,30 @@ unsafe impl<T: Array, S: Vector<Item=T::Item> + Spilled<ArrayVec<T>>> Vector for
     }
 }
 
-impl<T: Array, S: Extend<T::Item>> Extend<T::Item> for SmallVec<T, S> where SmallVec<T, S>: Vector {
+impl<T: Array<Item=u8>, S: Spilled<ArrayVec<T>>> SmallVec<T, S> where SmallVec<T, S>: Vector<Item=u8> {
+    /// Formats `args` into a new vector, spilling if it doesn't fit inline.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a formatting trait implementation returns an error, as
+    /// `format!` does.
+    pub fn format(args: fmt::Arguments) -> Self {
+        let mut v = Self::new();
+        fmt::Write::write_fmt(&mut v, args).expect("a formatting trait implementation returned an error");
+        v
+    }
+}
+
+impl<T: Array<Item=u8>, S: Spilled<ArrayVec<T>>> fmt::Write for SmallVec<T, S> where SmallVec<T, S>: Vector<Item=u8> {
+    #[inline]
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.reserve(s.len());
+        s.bytes().for_each(|b| self.push(b));
+        Ok(())
+    }
+}
+
+impl<T: Array, S: Spilled<ArrayVec<T>> + Extend<T::Item>> Extend<T::Item> for SmallVec<T, S> where SmallVec<T, S>: Vector {
     fn extend<I: IntoIterator<Item=T::Item>>(&mut self, iter: I) {
         let iter = iter.into_iter();
         self.reserve(iter.size_hint().0);
@@ -162,7 +292,7 @@ impl<T: Array, S: Extend<T::Item>> Extend<T::Item> for SmallVec<T, S> where Smal
     }
 }
 
-impl<T: Array, S: Extend<T::Item>> FromIterator<T::Item> for SmallVec<T, S> where SmallVec<T, S>: Vector {
+impl<T: Array, S: Spilled<ArrayVec<T>> + Extend<T::Item>> FromIterator<T::Item> for SmallVec<T, S> where SmallVec<T, S>: Vector {
     fn from_iter<I: IntoIterator<Item=T::Item>>(iter: I) -> Self {
         let mut s = Self::new();
         s.extend(iter);
@@ -194,7 +324,7 @@ impl<T1: Iterator, T2: Iterator<Item=T1::Item>> Iterator for SmallVecIter<T1, T2
     }
 }
 
-impl<T: Array, S: IntoIterator<Item=<ArrayVec<T> as IntoIterator>::Item>> IntoIterator for SmallVec<T, S> where ArrayVec<T>: IntoIterator {
+impl<T: Array, S: Spilled<ArrayVec<T>> + IntoIterator<Item=<ArrayVec<T> as IntoIterator>::Item>> IntoIterator for SmallVec<T, S> where ArrayVec<T>: IntoIterator {
     type Item = <S as IntoIterator>::Item;
     type IntoIter = SmallVecIter<<ArrayVec<T> as IntoIterator>::IntoIter, <S as IntoIterator>::IntoIter>;
 
@@ -207,7 +337,7 @@ impl<T: Array, S: IntoIterator<Item=<ArrayVec<T> as IntoIterator>::Item>> IntoIt
     }
 }
 
-impl<T: Array, S: Deref> Deref for SmallVec<T, S> where ArrayVec<T>: Deref<Target=S::Target>  {
+impl<T: Array, S: Spilled<ArrayVec<T>> + Deref> Deref for SmallVec<T, S> where ArrayVec<T>: Deref<Target=S::Target>  {
     type Target = S::Target;
 
     fn deref(&self) -> &Self::Target {
@@ -216,27 +346,27 @@ impl<T: Array, S: Deref> Deref for SmallVec<T, S> where ArrayVec<T>: Deref<Targe
     }
 }
 
-impl<T: Array, S: DerefMut> DerefMut for SmallVec<T, S> where ArrayVec<T>: DerefMut + Deref<Target=S::Target> {
+impl<T: Array, S: Spilled<ArrayVec<T>> + DerefMut> DerefMut for SmallVec<T, S> where ArrayVec<T>: DerefMut + Deref<Target=S::Target> {
     fn deref_mut(&mut self) -> &mut Self::Target {
         let v = self.0.as_mut();
         coalesce!(2 => |v| v.deref_mut())
     }
 }
 
-impl<T: Array, S> Default for SmallVec<T, S> {
+impl<T: Array, S: Spilled<ArrayVec<T>>> Default for SmallVec<T, S> where S::Alloc: Default {
     fn default() -> Self {
-        SmallVec(Coalesce2::A(Default::default()))
+        SmallVec(Coalesce2::A(Default::default()), Default::default())
     }
 }
 
-impl<T: Array, S: Hash> Hash for SmallVec<T, S> where ArrayVec<T>: Hash {
+impl<T: Array, S: Spilled<ArrayVec<T>> + Hash> Hash for SmallVec<T, S> where ArrayVec<T>: Hash {
     fn hash<H: Hasher>(&self, h: &mut H) {
         let v = self.0.as_ref();
         coalesce!(2 => |v| v.hash(h))
     }
 }
 
-impl<T: Array, S: fmt::Debug + Vector<Item=T::Item> + Spilled<ArrayVec<T>>> fmt::Debug for SmallVec<T, S> where ArrayVec<T>: fmt::Debug {
+impl<T: Array, S: fmt::Debug + Vector<Item=T::Item> + Spilled<ArrayVec<T>>> fmt::Debug for SmallVec<T, S> where ArrayVec<T>: fmt::Debug, S::Alloc: Default {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
         let v = self.0.as_ref();
         fmt.debug_struct("SmallVec")
@@ -247,7 +377,23 @@ impl<T: Array, S: fmt::Debug + Vector<Item=T::Item> + Spilled<ArrayVec<T>>> fmt:
     }
 }
 
-impl<'a, T: Array + 'a, S: Vector<Item=T::Item> + 'a> AsRef<dyn Vector<Item=T::Item> + 'a> for SmallVec<T, S> where T::Item: 'a, T::Index: 'a {
+impl<T: Array, S: Vector<Item=T::Item> + Spilled<ArrayVec<T>>> AsRef<[T::Item]> for SmallVec<T, S> {
+    #[inline]
+    fn as_ref(&self) -> &[T::Item] {
+        let v = self.0.as_ref();
+        coalesce!(2 => |v| v.as_slice())
+    }
+}
+
+impl<T: Array, S: Vector<Item=T::Item> + Spilled<ArrayVec<T>>> AsMut<[T::Item]> for SmallVec<T, S> {
+    #[inline]
+    fn as_mut(&mut self) -> &mut [T::Item] {
+        let v = self.0.as_mut();
+        coalesce!(2 => |v| v.as_mut_slice())
+    }
+}
+
+impl<'a, T: Array + 'a, S: Vector<Item=T::Item> + Spilled<ArrayVec<T>> + 'a> AsRef<dyn Vector<Item=T::Item> + 'a> for SmallVec<T, S> where T::Item: 'a, T::Index: 'a {
     fn as_ref(&self) -> &(dyn Vector<Item=T::Item> + 'a) {
         let v = self.0.as_ref();
         coalesce!(2 => |v| v as &_)
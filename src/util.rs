@@ -1,3 +1,5 @@
+use core::ops::{Bound, RangeBounds};
+
 pub trait PointerExt {
     unsafe fn uoffset(self, off: usize) -> Self;
 }
@@ -5,13 +7,46 @@ pub trait PointerExt {
 impl<T> PointerExt for *const T {
     #[inline]
     unsafe fn uoffset(self, off: usize) -> Self {
-        self.offset(off as isize)
+        self.add(off)
     }
 }
 
 impl<T> PointerExt for *mut T {
     #[inline]
     unsafe fn uoffset(self, off: usize) -> Self {
-        self.offset(off as isize)
+        self.add(off)
+    }
+}
+
+pub fn range_bounds<R: RangeBounds<usize>>(range: R, len: usize) -> (usize, usize) {
+    let start = match range.start_bound() {
+        Bound::Included(&start) => start,
+        Bound::Excluded(&start) => start.checked_add(1).expect("range start overflow"),
+        Bound::Unbounded => 0,
+    };
+    let end = match range.end_bound() {
+        Bound::Included(&end) => end.checked_add(1).expect("range end overflow"),
+        Bound::Excluded(&end) => end,
+        Bound::Unbounded => len,
+    };
+    assert!(start <= end, "range start {} greater than end {}", start, end);
+    assert!(end <= len, "range end {} out of bounds for length {}", end, len);
+    (start, end)
+}
+
+pub fn sorted_range<T, K: Ord + ?Sized, R: RangeBounds<K>, F: Fn(&T) -> &K>(slice: &[T], range: R, key: F) -> (usize, usize) {
+    let start = match range.start_bound() {
+        Bound::Included(start) => slice.partition_point(|v| key(v) < start),
+        Bound::Excluded(start) => slice.partition_point(|v| key(v) <= start),
+        Bound::Unbounded => 0,
+    };
+    let end = match range.end_bound() {
+        Bound::Included(end) => slice.partition_point(|v| key(v) <= end),
+        Bound::Excluded(end) => slice.partition_point(|v| key(v) < end),
+        Bound::Unbounded => slice.len(),
+    };
+    if let (Bound::Included(start) | Bound::Excluded(start), Bound::Included(end) | Bound::Excluded(end)) = (range.start_bound(), range.end_bound()) {
+        assert!(start <= end, "range start is greater than range end");
     }
+    (start, end.max(start))
 }
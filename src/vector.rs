@@ -1,8 +1,19 @@
-use std::mem::transmute;
-use std::ptr::{read, write, swap, copy};
-use std::slice::{from_raw_parts, from_raw_parts_mut};
+use core::ptr::{read, write, swap, copy, copy_nonoverlapping, drop_in_place};
+use core::slice::{from_raw_parts, from_raw_parts_mut};
+use core::ops::RangeBounds;
+#[cfg(feature = "alloc")]
+use alloc::{vec::Vec, boxed::Box};
+#[cfg(all(feature = "unstable", feature = "alloc"))]
+use {core::alloc::Allocator, alloc::alloc::Global};
+use crate::drain::{Drain, Splice};
 use crate::util::PointerExt;
 
+/// A generic interface for `Vec`-like containers.
+///
+/// # Safety
+///
+/// `as_ptr` and `as_mut_ptr` must address `capacity()` contiguous items, the
+/// first `len()` of which are initialized.
 pub unsafe trait Vector {
     type Item;
 
@@ -17,21 +28,40 @@ pub unsafe trait Vector {
     fn reserve(&mut self, additional: usize);
     fn reserve_exact(&mut self, additional: usize);
     fn shrink_to_fit(&mut self);
-    fn into_boxed_slice(self) -> Box<[Self::Item]>;
+    #[cfg(feature = "alloc")]
+    fn into_boxed_slice(self) -> Box<[Self::Item]> where Self: Sized {
+        self.into_vec().into_boxed_slice()
+    }
+
+    #[cfg(feature = "alloc")]
+    fn into_vec(mut self) -> Vec<Self::Item> where Self: Sized {
+        let len = self.len();
+        let mut v = Vec::with_capacity(len);
+        unsafe {
+            self.set_len(0);
+            copy_nonoverlapping(self.as_ptr(), v.as_mut_ptr(), len);
+            v.set_len(len);
+        }
+        v
+    }
 
     fn truncate(&mut self, len: usize) {
         let s_len = self.len();
         assert!(len <= s_len);
-        let ptr = self.as_mut_ptr();
 
         unsafe {
             self.set_len(len);
+            let ptr = self.as_mut_ptr();
             for i in len..s_len {
                 read(ptr.uoffset(i));
             }
         }
     }
 
+    /// # Safety
+    ///
+    /// `len` must not exceed the capacity, and the items up to `len` must be
+    /// initialized.
     unsafe fn set_len(&mut self, len: usize);
 
     fn swap_remove(&mut self, index: usize) -> Self::Item {
@@ -65,29 +95,94 @@ pub unsafe trait Vector {
         assert!(index < len);
         unsafe {
             let ptr = self.as_mut_ptr().uoffset(index);
-            self.set_len(len - 1);
             let v = read(ptr);
             copy(ptr.uoffset(1), ptr, len - index - 1);
+            self.set_len(len - 1);
             v
         }
     }
 
     fn retain<F: FnMut(&Self::Item) -> bool>(&mut self, mut f: F) where Self: Sized {
-        let len = self.len();
-        let mut del = 0;
+        self.retain_mut(|v| f(v))
+    }
+
+    fn retain_mut<F: FnMut(&mut Self::Item) -> bool>(&mut self, mut f: F) where Self: Sized {
+        let mut guard = CompactGuard::new(self, 0);
+        let ptr = guard.vec.as_mut_ptr();
         unsafe {
-            let v = self.as_mut_ptr();
+            while guard.read < guard.len {
+                let cur = ptr.uoffset(guard.read);
+                let keep = f(&mut *cur);
+                guard.read += 1;
+                if keep {
+                    copy(cur, ptr.uoffset(guard.write), 1);
+                    guard.write += 1;
+                } else {
+                    drop_in_place(cur);
+                }
+            }
+        }
+    }
+
+    fn dedup_by<F: FnMut(&mut Self::Item, &mut Self::Item) -> bool>(&mut self, mut same_bucket: F) where Self: Sized {
+        if self.len() <= 1 {
+            return
+        }
 
-            for i in 0..len {
-                if !f(transmute(v.uoffset(i) as *const _)) {
-                    del += 1;
-                } else if del > 0 {
-                    swap(v.uoffset(i - del), v.uoffset(i));
+        let mut guard = CompactGuard::new(self, 1);
+        let ptr = guard.vec.as_mut_ptr();
+        unsafe {
+            while guard.read < guard.len {
+                let cur = ptr.uoffset(guard.read);
+                let duplicate = same_bucket(&mut *cur, &mut *ptr.uoffset(guard.write - 1));
+                guard.read += 1;
+                if duplicate {
+                    drop_in_place(cur);
+                } else {
+                    copy(cur, ptr.uoffset(guard.write), 1);
+                    guard.write += 1;
                 }
             }
         }
-        if del > 0 {
-            self.truncate(len - del);
+    }
+
+    fn dedup_by_key<K: PartialEq, F: FnMut(&mut Self::Item) -> K>(&mut self, mut key: F) where Self: Sized {
+        self.dedup_by(|a, b| key(a) == key(b))
+    }
+
+    fn dedup(&mut self) where Self: Sized, Self::Item: PartialEq {
+        self.dedup_by(|a, b| a == b)
+    }
+
+    fn drain<R: RangeBounds<usize>>(&mut self, range: R) -> Drain<'_, Self> where Self: Sized {
+        Drain::new(self, range)
+    }
+
+    fn splice<R: RangeBounds<usize>, I: IntoIterator<Item=Self::Item>>(&mut self, range: R, replace_with: I) -> Splice<'_, Self, I::IntoIter> where Self: Sized {
+        Splice::new(self, range, replace_with.into_iter())
+    }
+
+    fn split_off(&mut self, at: usize) -> Self where Self: Sized {
+        let len = self.len();
+        assert!(at <= len);
+        let other_len = len - at;
+        let mut other = Self::with_capacity(other_len);
+        unsafe {
+            self.set_len(at);
+            copy_nonoverlapping(self.as_ptr().uoffset(at), other.as_mut_ptr(), other_len);
+            other.set_len(other_len);
+        }
+        other
+    }
+
+    fn append(&mut self, other: &mut Self) where Self: Sized {
+        let count = other.len();
+        self.reserve(count);
+        let len = self.len();
+        unsafe {
+            other.set_len(0);
+            copy_nonoverlapping(other.as_ptr(), self.as_mut_ptr().uoffset(len), count);
+            self.set_len(len + count);
         }
     }
 
@@ -155,6 +250,10 @@ pub unsafe trait Vector {
         unsafe { from_raw_parts_mut(self.as_mut_ptr(), self.len()) }
     }
 
+    /// # Safety
+    ///
+    /// Any newly exposed items are uninitialized and must be written before
+    /// they are read or dropped.
     unsafe fn uninitialized_resize(&mut self, new_len: usize) {
         let len = self.len();
         if new_len > len {
@@ -164,6 +263,44 @@ pub unsafe trait Vector {
     }
 }
 
+// Compacts a vector in place: items before `write` are kept, items from
+// `read` on are yet to be visited, and everything between has been moved out
+// or dropped. The length stays at zero until the guard closes the gap, so a
+// panic part way through leaks nothing and drops nothing twice.
+struct CompactGuard<'v, V: Vector> {
+    vec: &'v mut V,
+    read: usize,
+    write: usize,
+    len: usize,
+}
+
+impl<'v, V: Vector> CompactGuard<'v, V> {
+    fn new(vec: &'v mut V, start: usize) -> Self {
+        let len = vec.len();
+        unsafe { vec.set_len(0) }
+        CompactGuard {
+            vec,
+            read: start,
+            write: start,
+            len,
+        }
+    }
+}
+
+impl<'v, V: Vector> Drop for CompactGuard<'v, V> {
+    fn drop(&mut self) {
+        let tail = self.len - self.read;
+        unsafe {
+            if tail > 0 && self.read != self.write {
+                let ptr = self.vec.as_mut_ptr();
+                copy(ptr.uoffset(self.read), ptr.uoffset(self.write), tail);
+            }
+            self.vec.set_len(self.write + tail);
+        }
+    }
+}
+
+#[cfg(all(feature = "alloc", not(feature = "unstable")))]
 unsafe impl<T> Vector for Vec<T> {
     type Item = T;
 
@@ -174,19 +311,95 @@ unsafe impl<T> Vector for Vec<T> {
     #[inline] fn reserve_exact(&mut self, additional: usize) { Vec::reserve_exact(self, additional) }
     #[inline] fn shrink_to_fit(&mut self) { Vec::shrink_to_fit(self) }
     #[inline] fn into_boxed_slice(self) -> Box<[T]> { Vec::into_boxed_slice(self) }
+    #[inline] fn into_vec(self) -> Vec<T> { self }
+    #[inline] fn truncate(&mut self, len: usize) { Vec::truncate(self, len) }
+    #[inline] unsafe fn set_len(&mut self, len: usize) { Vec::set_len(self, len) }
+    #[inline] fn swap_remove(&mut self, index: usize) -> T { Vec::swap_remove(self, index) }
+    #[inline] fn insert(&mut self, index: usize, element: T) { Vec::insert(self, index, element) }
+    #[inline] fn remove(&mut self, index: usize) -> T { Vec::remove(self, index) }
+    #[inline] fn retain<F: FnMut(&T) -> bool>(&mut self, f: F) { Vec::retain(self, f) }
+    #[inline] fn retain_mut<F: FnMut(&mut T) -> bool>(&mut self, f: F) { Vec::retain_mut(self, f) }
+    #[inline] fn dedup_by<F: FnMut(&mut T, &mut T) -> bool>(&mut self, same_bucket: F) { Vec::dedup_by(self, same_bucket) }
+    #[inline] fn dedup_by_key<K: PartialEq, F: FnMut(&mut T) -> K>(&mut self, key: F) { Vec::dedup_by_key(self, key) }
+    #[inline] fn dedup(&mut self) where T: PartialEq { Vec::dedup(self) }
+    #[inline] fn drain<R: RangeBounds<usize>>(&mut self, range: R) -> Drain<'_, Self> { Drain::from_vec(Vec::drain(self, range)) }
+    #[inline] fn splice<R: RangeBounds<usize>, I: IntoIterator<Item=T>>(&mut self, range: R, replace_with: I) -> Splice<'_, Self, I::IntoIter> { Splice::from_vec(Vec::splice(self, range, replace_with)) }
+    #[inline] fn split_off(&mut self, at: usize) -> Self { Vec::split_off(self, at) }
+    #[inline] fn append(&mut self, other: &mut Self) { Vec::append(self, other) }
+    #[inline] fn push(&mut self, value: T) { Vec::push(self, value) }
+    #[inline] fn pop(&mut self) -> Option<T> { Vec::pop(self) }
+    #[inline] fn clear(&mut self) { Vec::clear(self) }
+    #[inline] fn len(&self) -> usize { Vec::len(self) }
+    #[inline] fn is_empty(&self) -> bool { Vec::is_empty(self) }
+    #[inline] fn as_ptr(&self) -> *const T { Vec::as_ptr(self) }
+    #[inline] fn as_mut_ptr(&mut self) -> *mut T { Vec::as_mut_ptr(self) }
+    #[inline] fn as_slice(&self) -> &[T] { &self[..] }
+    #[inline] fn as_mut_slice(&mut self) -> &mut [T] { &mut self[..] }
+}
+
+/// An allocator that a `Vec` can be used as a `Vector` with.
+///
+/// `Vector::new` and `Vector::with_capacity` take no allocator and use
+/// `A::default()`, so `Vec<T, A>` is only a `Vector`, and can only back a
+/// `SmallVec`, if `A: Default`. A `SmallVec` built with `new_in` still spills
+/// into a clone of the allocator it was given.
+///
+/// The provided methods copy into the global allocator where a `Vector`
+/// method returns a global `Vec`, and fall back to this crate's own drain and
+/// splice.
+#[cfg(all(feature = "unstable", feature = "alloc"))]
+pub trait VectorAllocator: Allocator + Clone {
+    fn into_vec<T>(vec: Vec<T, Self>) -> Vec<T> {
+        vec.into_iter().collect()
+    }
+
+    fn drain<T, R: RangeBounds<usize>>(vec: &mut Vec<T, Self>, range: R) -> Drain<'_, Vec<T, Self>> where Self: Default {
+        Drain::new(vec, range)
+    }
+
+    fn splice<T, R: RangeBounds<usize>, I: IntoIterator<Item=T>>(vec: &mut Vec<T, Self>, range: R, replace_with: I) -> Splice<'_, Vec<T, Self>, I::IntoIter> where Self: Default {
+        Splice::new(vec, range, replace_with.into_iter())
+    }
+}
+
+#[cfg(all(feature = "unstable", feature = "alloc"))]
+impl VectorAllocator for Global {
+    #[inline] fn into_vec<T>(vec: Vec<T>) -> Vec<T> { vec }
+    #[inline] fn drain<T, R: RangeBounds<usize>>(vec: &mut Vec<T>, range: R) -> Drain<'_, Vec<T>> { Drain::from_vec(Vec::drain(vec, range)) }
+    #[inline] fn splice<T, R: RangeBounds<usize>, I: IntoIterator<Item=T>>(vec: &mut Vec<T>, range: R, replace_with: I) -> Splice<'_, Vec<T>, I::IntoIter> { Splice::from_vec(Vec::splice(vec, range, replace_with)) }
+}
+
+#[cfg(all(feature = "unstable", feature = "alloc"))]
+unsafe impl<T, A: VectorAllocator + Default> Vector for Vec<T, A> {
+    type Item = T;
+
+    #[inline] fn new() -> Self { Vec::new_in(A::default()) }
+    #[inline] fn with_capacity(cap: usize) -> Self { Vec::with_capacity_in(cap, A::default()) }
+    #[inline] fn capacity(&self) -> usize { Vec::capacity(self) }
+    #[inline] fn reserve(&mut self, additional: usize) { Vec::reserve(self, additional) }
+    #[inline] fn reserve_exact(&mut self, additional: usize) { Vec::reserve_exact(self, additional) }
+    #[inline] fn shrink_to_fit(&mut self) { Vec::shrink_to_fit(self) }
+    #[inline] fn into_vec(self) -> Vec<T> { A::into_vec(self) }
     #[inline] fn truncate(&mut self, len: usize) { Vec::truncate(self, len) }
     #[inline] unsafe fn set_len(&mut self, len: usize) { Vec::set_len(self, len) }
     #[inline] fn swap_remove(&mut self, index: usize) -> T { Vec::swap_remove(self, index) }
     #[inline] fn insert(&mut self, index: usize, element: T) { Vec::insert(self, index, element) }
     #[inline] fn remove(&mut self, index: usize) -> T { Vec::remove(self, index) }
     #[inline] fn retain<F: FnMut(&T) -> bool>(&mut self, f: F) { Vec::retain(self, f) }
+    #[inline] fn retain_mut<F: FnMut(&mut T) -> bool>(&mut self, f: F) { Vec::retain_mut(self, f) }
+    #[inline] fn dedup_by<F: FnMut(&mut T, &mut T) -> bool>(&mut self, same_bucket: F) { Vec::dedup_by(self, same_bucket) }
+    #[inline] fn dedup_by_key<K: PartialEq, F: FnMut(&mut T) -> K>(&mut self, key: F) { Vec::dedup_by_key(self, key) }
+    #[inline] fn dedup(&mut self) where T: PartialEq { Vec::dedup(self) }
+    #[inline] fn drain<R: RangeBounds<usize>>(&mut self, range: R) -> Drain<'_, Self> { A::drain(self, range) }
+    #[inline] fn splice<R: RangeBounds<usize>, I: IntoIterator<Item=T>>(&mut self, range: R, replace_with: I) -> Splice<'_, Self, I::IntoIter> { A::splice(self, range, replace_with) }
+    #[inline] fn append(&mut self, other: &mut Self) { Vec::append(self, other) }
     #[inline] fn push(&mut self, value: T) { Vec::push(self, value) }
     #[inline] fn pop(&mut self) -> Option<T> { Vec::pop(self) }
     #[inline] fn clear(&mut self) { Vec::clear(self) }
     #[inline] fn len(&self) -> usize { Vec::len(self) }
     #[inline] fn is_empty(&self) -> bool { Vec::is_empty(self) }
-    #[inline] fn as_ptr(&self) -> *const T { self[..].as_ptr() }
-    #[inline] fn as_mut_ptr(&mut self) -> *mut T { self[..].as_mut_ptr() }
+    #[inline] fn as_ptr(&self) -> *const T { Vec::as_ptr(self) }
+    #[inline] fn as_mut_ptr(&mut self) -> *mut T { Vec::as_mut_ptr(self) }
     #[inline] fn as_slice(&self) -> &[T] { &self[..] }
     #[inline] fn as_mut_slice(&mut self) -> &mut [T] { &mut self[..] }
 }
@@ -0,0 +1,310 @@
+use core::fmt;
+use core::mem::ManuallyDrop;
+use core::ptr::read;
+use core::ops::{Deref, DerefMut};
+use core::iter::FromIterator;
+use core::marker::PhantomData;
+use core::slice;
+use crate::{Array, ArrayVec, Vector, Drain, CapacityError};
+
+pub type ArrayBinaryHeap<A, O = Max> = VectorHeap<ArrayVec<A>, O>;
+#[cfg(feature = "alloc")]
+pub type SmallBinaryHeap<A, O = Max> = VectorHeap<crate::SmallVec<A>, O>;
+
+/// Decides which end of a `VectorHeap` is popped first.
+pub trait HeapOrder {
+    /// Returns whether `a` must be popped before `b`.
+    fn precedes<T: Ord + ?Sized>(a: &T, b: &T) -> bool;
+}
+
+/// Pops the greatest item first, like `std::collections::BinaryHeap`.
+pub struct Max;
+
+/// Pops the least item first.
+pub struct Min;
+
+impl HeapOrder for Max {
+    #[inline]
+    fn precedes<T: Ord + ?Sized>(a: &T, b: &T) -> bool {
+        a > b
+    }
+}
+
+impl HeapOrder for Min {
+    #[inline]
+    fn precedes<T: Ord + ?Sized>(a: &T, b: &T) -> bool {
+        a < b
+    }
+}
+
+/// A priority queue implemented as a binary heap over a `Vector`.
+pub struct VectorHeap<S, O = Max> {
+    vec: S,
+    _order: PhantomData<O>,
+}
+
+impl<S: Vector, O: HeapOrder> VectorHeap<S, O> where S::Item: Ord {
+    #[inline]
+    pub fn new() -> Self {
+        VectorHeap {
+            vec: S::new(),
+            _order: PhantomData,
+        }
+    }
+
+    #[inline]
+    pub fn with_capacity(cap: usize) -> Self {
+        VectorHeap {
+            vec: S::with_capacity(cap),
+            _order: PhantomData,
+        }
+    }
+
+    /// Returns the storage in arbitrary order.
+    #[inline]
+    pub fn into_inner(self) -> S {
+        self.vec
+    }
+
+    /// Returns the storage ordered so that the item that would have been
+    /// popped first comes last.
+    pub fn into_sorted_vec(mut self) -> S {
+        let mut end = self.vec.len();
+        while end > 1 {
+            end -= 1;
+            self.vec.as_mut_slice().swap(0, end);
+            Self::sift_down(&mut self.vec.as_mut_slice()[..end], 0);
+        }
+        self.vec
+    }
+
+    /// Items in arbitrary order.
+    #[inline]
+    pub fn as_slice(&self) -> &[S::Item] {
+        self.vec.as_slice()
+    }
+
+    #[inline]
+    pub fn iter(&self) -> slice::Iter<'_, S::Item> {
+        self.vec.as_slice().iter()
+    }
+
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.vec.capacity()
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.vec.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.vec.is_empty()
+    }
+
+    #[inline]
+    pub fn clear(&mut self) {
+        self.vec.clear()
+    }
+
+    /// Removes all items in arbitrary order.
+    #[inline]
+    pub fn drain(&mut self) -> Drain<'_, S> {
+        self.vec.drain(..)
+    }
+
+    #[inline]
+    pub fn peek(&self) -> Option<&S::Item> {
+        self.vec.as_slice().first()
+    }
+
+    /// Mutably borrows the first item, restoring the heap order once the
+    /// borrow ends.
+    #[inline]
+    pub fn peek_mut(&mut self) -> Option<PeekMut<'_, S, O>> {
+        if self.is_empty() {
+            None
+        } else {
+            Some(PeekMut {
+                heap: self,
+            })
+        }
+    }
+
+    /// # Panics
+    ///
+    /// Panics if the underlying vector is out of capacity.
+    pub fn push(&mut self, item: S::Item) {
+        self.vec.push(item);
+        let len = self.vec.len();
+        Self::sift_up(self.vec.as_mut_slice(), len - 1);
+    }
+
+    pub fn pop(&mut self) -> Option<S::Item> {
+        let len = self.vec.len();
+        if len > 1 {
+            self.vec.as_mut_slice().swap(0, len - 1);
+        }
+        let item = self.vec.pop();
+        Self::sift_down(self.vec.as_mut_slice(), 0);
+        item
+    }
+
+    fn sift_up(heap: &mut [S::Item], mut pos: usize) {
+        while pos > 0 {
+            let parent = (pos - 1) / 2;
+            if !O::precedes(&heap[pos], &heap[parent]) {
+                break
+            }
+            heap.swap(pos, parent);
+            pos = parent;
+        }
+    }
+
+    fn sift_down(heap: &mut [S::Item], mut pos: usize) {
+        loop {
+            let left = pos * 2 + 1;
+            if left >= heap.len() {
+                break
+            }
+
+            let right = left + 1;
+            let child = if right < heap.len() && O::precedes(&heap[right], &heap[left]) {
+                right
+            } else {
+                left
+            };
+
+            if !O::precedes(&heap[child], &heap[pos]) {
+                break
+            }
+            heap.swap(pos, child);
+            pos = child;
+        }
+    }
+
+    fn heapify(&mut self) {
+        let heap = self.vec.as_mut_slice();
+        for pos in (0..heap.len() / 2).rev() {
+            Self::sift_down(heap, pos);
+        }
+    }
+}
+
+impl<A: Array, O: HeapOrder> VectorHeap<ArrayVec<A>, O> where A::Item: Ord {
+    pub fn try_push(&mut self, item: A::Item) -> Result<(), CapacityError<A::Item>> {
+        self.vec.try_push(item)?;
+        let len = self.vec.len();
+        Self::sift_up(&mut self.vec, len - 1);
+        Ok(())
+    }
+}
+
+pub struct PeekMut<'a, S: Vector + 'a, O: HeapOrder + 'a> where S::Item: Ord {
+    heap: &'a mut VectorHeap<S, O>,
+}
+
+impl<'a, S: Vector, O: HeapOrder> PeekMut<'a, S, O> where S::Item: Ord {
+    #[inline]
+    pub fn pop(this: Self) -> S::Item {
+        // skip the sift in `Drop`, `pop` restores the order itself
+        let this = ManuallyDrop::new(this);
+        let heap = unsafe { read(&this.heap) };
+        heap.pop().expect("PeekMut on an empty heap")
+    }
+}
+
+impl<'a, S: Vector, O: HeapOrder> Drop for PeekMut<'a, S, O> where S::Item: Ord {
+    fn drop(&mut self) {
+        VectorHeap::<S, O>::sift_down(self.heap.vec.as_mut_slice(), 0)
+    }
+}
+
+impl<'a, S: Vector, O: HeapOrder> Deref for PeekMut<'a, S, O> where S::Item: Ord {
+    type Target = S::Item;
+
+    #[inline]
+    fn deref(&self) -> &S::Item {
+        &self.heap.vec.as_slice()[0]
+    }
+}
+
+impl<'a, S: Vector, O: HeapOrder> DerefMut for PeekMut<'a, S, O> where S::Item: Ord {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut S::Item {
+        &mut self.heap.vec.as_mut_slice()[0]
+    }
+}
+
+impl<S: Vector, O: HeapOrder> From<S> for VectorHeap<S, O> where S::Item: Ord {
+    fn from(vec: S) -> Self {
+        let mut heap = VectorHeap {
+            vec,
+            _order: PhantomData,
+        };
+        heap.heapify();
+        heap
+    }
+}
+
+impl<S: Vector + IntoIterator<Item=<S as Vector>::Item>, O> IntoIterator for VectorHeap<S, O> {
+    type Item = <S as Vector>::Item;
+    type IntoIter = S::IntoIter;
+
+    /// Iterates in arbitrary order.
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.vec.into_iter()
+    }
+}
+
+impl<'a, S: Vector, O: HeapOrder> IntoIterator for &'a VectorHeap<S, O> where S::Item: Ord {
+    type Item = &'a S::Item;
+    type IntoIter = slice::Iter<'a, S::Item>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<S: Vector, O: HeapOrder> Extend<S::Item> for VectorHeap<S, O> where S::Item: Ord {
+    fn extend<I: IntoIterator<Item=S::Item>>(&mut self, iter: I) {
+        for item in iter {
+            self.push(item);
+        }
+    }
+}
+
+impl<S: Vector, O: HeapOrder> FromIterator<S::Item> for VectorHeap<S, O> where S::Item: Ord {
+    fn from_iter<I: IntoIterator<Item=S::Item>>(iter: I) -> Self {
+        let mut vec = S::new();
+        for item in iter {
+            vec.push(item);
+        }
+        vec.into()
+    }
+}
+
+impl<S: Clone, O> Clone for VectorHeap<S, O> {
+    fn clone(&self) -> Self {
+        VectorHeap {
+            vec: self.vec.clone(),
+            _order: PhantomData,
+        }
+    }
+}
+
+impl<S: Vector, O: HeapOrder> Default for VectorHeap<S, O> where S::Item: Ord {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S: Vector, O: HeapOrder> fmt::Debug for VectorHeap<S, O> where S::Item: Ord + fmt::Debug {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_list().entries(self.iter()).finish()
+    }
+}
@@ -0,0 +1,563 @@
+use core::fmt;
+use core::mem::replace;
+use core::borrow::Borrow;
+use core::ops::{Index, IndexMut, RangeBounds};
+use core::iter::{FromIterator, FusedIterator};
+use core::hash::{Hash, Hasher};
+use core::slice;
+use crate::util::sorted_range;
+use crate::{Array, ArrayVec, Vector, CapacityError};
+
+// Borrowing a value through `S::Item` only requires `S` to outlive the
+// borrow. Naming `(K, V)` would also need `K` to outlive it, which `get` and
+// `Index` have no way to state.
+trait Pair {
+    type Value;
+
+    fn value(&self) -> &Self::Value;
+    fn value_mut(&mut self) -> &mut Self::Value;
+}
+
+impl<K, V> Pair for (K, V) {
+    type Value = V;
+
+    #[inline] fn value(&self) -> &V { &self.1 }
+    #[inline] fn value_mut(&mut self) -> &mut V { &mut self.1 }
+}
+
+#[inline]
+fn value<S: Vector>(vec: &S, index: usize) -> &<S::Item as Pair>::Value where S::Item: Pair {
+    vec.as_slice()[index].value()
+}
+
+#[inline]
+fn value_mut<S: Vector>(vec: &mut S, index: usize) -> &mut <S::Item as Pair>::Value where S::Item: Pair {
+    vec.as_mut_slice()[index].value_mut()
+}
+
+pub type ArrayMap<A> = VectorMap<ArrayVec<A>>;
+#[cfg(feature = "alloc")]
+pub type SmallMap<A> = VectorMap<crate::SmallVec<A>>;
+
+/// A map stored as a `Vector` of `(K, V)` pairs, kept sorted by key.
+///
+/// Lookups are a binary search, while insertion and removal shift the
+/// entries that follow, so it suits small tables that fit in an `ArrayVec`
+/// or `SmallVec`.
+pub struct VectorMap<S: Vector> {
+    vec: S,
+}
+
+impl<K: Ord, V, S: Vector<Item=(K, V)>> VectorMap<S> {
+    #[inline]
+    pub fn new() -> Self {
+        VectorMap {
+            vec: S::new(),
+        }
+    }
+
+    #[inline]
+    pub fn with_capacity(cap: usize) -> Self {
+        VectorMap {
+            vec: S::with_capacity(cap),
+        }
+    }
+
+    #[inline]
+    pub fn into_inner(self) -> S {
+        self.vec
+    }
+
+    #[inline]
+    pub fn as_slice(&self) -> &[(K, V)] {
+        self.vec.as_slice()
+    }
+
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.vec.capacity()
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.vec.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.vec.is_empty()
+    }
+
+    #[inline]
+    pub fn clear(&mut self) {
+        self.vec.clear()
+    }
+
+    #[inline]
+    fn search<Q: Ord + ?Sized>(&self, key: &Q) -> Result<usize, usize> where K: Borrow<Q> {
+        self.vec.as_slice().binary_search_by(|(k, _)| k.borrow().cmp(key))
+    }
+
+    #[inline]
+    pub fn contains_key<Q: Ord + ?Sized>(&self, key: &Q) -> bool where K: Borrow<Q> {
+        self.search(key).is_ok()
+    }
+
+    #[inline]
+    pub fn get<Q: Ord + ?Sized>(&self, key: &Q) -> Option<&V> where K: Borrow<Q> {
+        match self.search(key) {
+            Ok(i) => Some(value(&self.vec, i)),
+            Err(..) => None,
+        }
+    }
+
+    #[inline]
+    pub fn get_key_value<Q: Ord + ?Sized>(&self, key: &Q) -> Option<(&K, &V)> where K: Borrow<Q> {
+        match self.search(key) {
+            Ok(i) => {
+                let (k, v) = &self.vec.as_slice()[i];
+                Some((k, v))
+            },
+            Err(..) => None,
+        }
+    }
+
+    #[inline]
+    pub fn get_mut<Q: Ord + ?Sized>(&mut self, key: &Q) -> Option<&mut V> where K: Borrow<Q> {
+        match self.search(key) {
+            Ok(i) => Some(value_mut(&mut self.vec, i)),
+            Err(..) => None,
+        }
+    }
+
+    /// Inserts a value, returning the previous value stored under `key`.
+    ///
+    /// The key itself is not updated if it was already present.
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        match self.search(&key) {
+            Ok(i) => Some(replace(&mut self.vec.as_mut_slice()[i].1, value)),
+            Err(i) => {
+                self.vec.insert(i, (key, value));
+                None
+            },
+        }
+    }
+
+    #[inline]
+    pub fn remove<Q: Ord + ?Sized>(&mut self, key: &Q) -> Option<V> where K: Borrow<Q> {
+        self.remove_entry(key).map(|(_, v)| v)
+    }
+
+    pub fn remove_entry<Q: Ord + ?Sized>(&mut self, key: &Q) -> Option<(K, V)> where K: Borrow<Q> {
+        match self.search(key) {
+            Ok(i) => Some(self.vec.remove(i)),
+            Err(..) => None,
+        }
+    }
+
+    pub fn entry(&mut self, key: K) -> Entry<'_, K, V, S> {
+        match self.search(&key) {
+            Ok(index) => Entry::Occupied(OccupiedEntry {
+                map: self,
+                index,
+            }),
+            Err(index) => Entry::Vacant(VacantEntry {
+                map: self,
+                key,
+                index,
+            }),
+        }
+    }
+
+    pub fn retain<F: FnMut(&K, &mut V) -> bool>(&mut self, mut f: F) {
+        self.vec.retain_mut(|(k, v)| f(k, v))
+    }
+
+    #[inline]
+    pub fn first_key_value(&self) -> Option<(&K, &V)> {
+        self.vec.as_slice().first().map(|(k, v)| (k, v))
+    }
+
+    #[inline]
+    pub fn last_key_value(&self) -> Option<(&K, &V)> {
+        self.vec.as_slice().last().map(|(k, v)| (k, v))
+    }
+
+    #[inline]
+    pub fn pop_first(&mut self) -> Option<(K, V)> {
+        if self.is_empty() {
+            None
+        } else {
+            Some(self.vec.remove(0))
+        }
+    }
+
+    #[inline]
+    pub fn pop_last(&mut self) -> Option<(K, V)> {
+        self.vec.pop()
+    }
+
+    #[inline]
+    pub fn iter(&self) -> VectorMapIter<'_, K, V> {
+        VectorMapIter(self.vec.as_slice().iter())
+    }
+
+    #[inline]
+    pub fn iter_mut(&mut self) -> VectorMapIterMut<'_, K, V> {
+        VectorMapIterMut(self.vec.as_mut_slice().iter_mut())
+    }
+
+    #[inline]
+    pub fn keys(&self) -> VectorMapKeys<'_, K, V> {
+        VectorMapKeys(self.vec.as_slice().iter())
+    }
+
+    #[inline]
+    pub fn values(&self) -> VectorMapValues<'_, K, V> {
+        VectorMapValues(self.vec.as_slice().iter())
+    }
+
+    /// Iterates over the entries whose keys fall within `range`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the start of the range is greater than its end.
+    pub fn range<Q: Ord + ?Sized, R: RangeBounds<Q>>(&self, range: R) -> VectorMapIter<'_, K, V> where K: Borrow<Q> {
+        let slice = self.vec.as_slice();
+        let (start, end) = sorted_range(slice, range, |(k, _)| k.borrow());
+        VectorMapIter(slice[start..end].iter())
+    }
+
+    pub fn range_mut<Q: Ord + ?Sized, R: RangeBounds<Q>>(&mut self, range: R) -> VectorMapIterMut<'_, K, V> where K: Borrow<Q> {
+        let slice = self.vec.as_mut_slice();
+        let (start, end) = sorted_range(slice, range, |(k, _)| k.borrow());
+        VectorMapIterMut(slice[start..end].iter_mut())
+    }
+}
+
+impl<K: Ord, V, A: Array<Item=(K, V)>> VectorMap<ArrayVec<A>> {
+    /// Inserts a value, or returns it in the error when a new entry is
+    /// needed and the map is full.
+    pub fn try_insert(&mut self, key: K, value: V) -> Result<Option<V>, CapacityError<(K, V)>> {
+        match self.search(&key) {
+            Ok(i) => Ok(Some(replace(&mut self.vec[i].1, value))),
+            Err(i) => self.vec.try_insert(i, (key, value)).map(|()| None),
+        }
+    }
+}
+
+pub enum Entry<'a, K: 'a, V: 'a, S: Vector<Item=(K, V)> + 'a> {
+    Vacant(VacantEntry<'a, K, V, S>),
+    Occupied(OccupiedEntry<'a, K, V, S>),
+}
+
+pub struct VacantEntry<'a, K: 'a, V: 'a, S: Vector<Item=(K, V)> + 'a> {
+    map: &'a mut VectorMap<S>,
+    key: K,
+    index: usize,
+}
+
+pub struct OccupiedEntry<'a, K: 'a, V: 'a, S: Vector<Item=(K, V)> + 'a> {
+    map: &'a mut VectorMap<S>,
+    index: usize,
+}
+
+impl<'a, K, V, S: Vector<Item=(K, V)>> Entry<'a, K, V, S> {
+    #[inline]
+    pub fn key(&self) -> &K {
+        match self {
+            Entry::Vacant(e) => e.key(),
+            Entry::Occupied(e) => e.key(),
+        }
+    }
+
+    #[inline]
+    pub fn or_insert(self, default: V) -> &'a mut V {
+        self.or_insert_with(|| default)
+    }
+
+    #[inline]
+    pub fn or_insert_with<F: FnOnce() -> V>(self, default: F) -> &'a mut V {
+        match self {
+            Entry::Vacant(e) => e.insert(default()),
+            Entry::Occupied(e) => e.into_mut(),
+        }
+    }
+
+    #[inline]
+    pub fn or_default(self) -> &'a mut V where V: Default {
+        self.or_insert_with(V::default)
+    }
+
+    #[inline]
+    pub fn and_modify<F: FnOnce(&mut V)>(mut self, f: F) -> Self {
+        if let Entry::Occupied(e) = &mut self {
+            f(e.get_mut())
+        }
+        self
+    }
+}
+
+impl<'a, K, V, S: Vector<Item=(K, V)>> VacantEntry<'a, K, V, S> {
+    #[inline]
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
+    #[inline]
+    pub fn into_key(self) -> K {
+        self.key
+    }
+
+    /// # Panics
+    ///
+    /// Panics if the underlying vector is out of capacity.
+    #[inline]
+    pub fn insert(self, value: V) -> &'a mut V {
+        self.map.vec.insert(self.index, (self.key, value));
+        &mut self.map.vec.as_mut_slice()[self.index].1
+    }
+}
+
+impl<'a, K, V, S: Vector<Item=(K, V)>> OccupiedEntry<'a, K, V, S> {
+    #[inline]
+    pub fn key(&self) -> &K {
+        &self.map.vec.as_slice()[self.index].0
+    }
+
+    #[inline]
+    pub fn get(&self) -> &V {
+        &self.map.vec.as_slice()[self.index].1
+    }
+
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut V {
+        &mut self.map.vec.as_mut_slice()[self.index].1
+    }
+
+    #[inline]
+    pub fn into_mut(self) -> &'a mut V {
+        &mut self.map.vec.as_mut_slice()[self.index].1
+    }
+
+    #[inline]
+    pub fn insert(&mut self, value: V) -> V {
+        replace(self.get_mut(), value)
+    }
+
+    #[inline]
+    pub fn remove(self) -> V {
+        self.remove_entry().1
+    }
+
+    #[inline]
+    pub fn remove_entry(self) -> (K, V) {
+        self.map.vec.remove(self.index)
+    }
+}
+
+pub struct VectorMapIter<'a, K: 'a, V: 'a>(slice::Iter<'a, (K, V)>);
+
+impl<'a, K, V> Iterator for VectorMapIter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(|(k, v)| (k, v))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl<'a, K, V> DoubleEndedIterator for VectorMapIter<'a, K, V> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.0.next_back().map(|(k, v)| (k, v))
+    }
+}
+
+impl<'a, K, V> ExactSizeIterator for VectorMapIter<'a, K, V> { }
+impl<'a, K, V> FusedIterator for VectorMapIter<'a, K, V> { }
+
+impl<'a, K, V> Clone for VectorMapIter<'a, K, V> {
+    fn clone(&self) -> Self {
+        VectorMapIter(self.0.clone())
+    }
+}
+
+pub struct VectorMapIterMut<'a, K: 'a, V: 'a>(slice::IterMut<'a, (K, V)>);
+
+impl<'a, K, V> Iterator for VectorMapIterMut<'a, K, V> {
+    type Item = (&'a K, &'a mut V);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(|(k, v)| (&*k, v))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl<'a, K, V> DoubleEndedIterator for VectorMapIterMut<'a, K, V> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.0.next_back().map(|(k, v)| (&*k, v))
+    }
+}
+
+impl<'a, K, V> ExactSizeIterator for VectorMapIterMut<'a, K, V> { }
+impl<'a, K, V> FusedIterator for VectorMapIterMut<'a, K, V> { }
+
+pub struct VectorMapKeys<'a, K: 'a, V: 'a>(slice::Iter<'a, (K, V)>);
+
+impl<'a, K, V> Iterator for VectorMapKeys<'a, K, V> {
+    type Item = &'a K;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(|(k, _)| k)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl<'a, K, V> DoubleEndedIterator for VectorMapKeys<'a, K, V> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.0.next_back().map(|(k, _)| k)
+    }
+}
+
+impl<'a, K, V> ExactSizeIterator for VectorMapKeys<'a, K, V> { }
+impl<'a, K, V> FusedIterator for VectorMapKeys<'a, K, V> { }
+
+pub struct VectorMapValues<'a, K: 'a, V: 'a>(slice::Iter<'a, (K, V)>);
+
+impl<'a, K, V> Iterator for VectorMapValues<'a, K, V> {
+    type Item = &'a V;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(|(_, v)| v)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl<'a, K, V> DoubleEndedIterator for VectorMapValues<'a, K, V> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.0.next_back().map(|(_, v)| v)
+    }
+}
+
+impl<'a, K, V> ExactSizeIterator for VectorMapValues<'a, K, V> { }
+impl<'a, K, V> FusedIterator for VectorMapValues<'a, K, V> { }
+
+impl<K: Ord, V, S: Vector<Item=(K, V)> + IntoIterator<Item=(K, V)>> IntoIterator for VectorMap<S> {
+    type Item = (K, V);
+    type IntoIter = S::IntoIter;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.vec.into_iter()
+    }
+}
+
+impl<'a, K: Ord + 'a, V: 'a, S: Vector<Item=(K, V)>> IntoIterator for &'a VectorMap<S> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = VectorMapIter<'a, K, V>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, K: Ord + 'a, V: 'a, S: Vector<Item=(K, V)>> IntoIterator for &'a mut VectorMap<S> {
+    type Item = (&'a K, &'a mut V);
+    type IntoIter = VectorMapIterMut<'a, K, V>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+impl<K: Ord, V, S: Vector<Item=(K, V)>> Extend<(K, V)> for VectorMap<S> {
+    fn extend<I: IntoIterator<Item=(K, V)>>(&mut self, iter: I) {
+        for (k, v) in iter {
+            self.insert(k, v);
+        }
+    }
+}
+
+impl<K: Ord, V, S: Vector<Item=(K, V)>> FromIterator<(K, V)> for VectorMap<S> {
+    fn from_iter<I: IntoIterator<Item=(K, V)>>(iter: I) -> Self {
+        let mut map = Self::new();
+        map.extend(iter);
+        map
+    }
+}
+
+impl<K: Ord + Borrow<Q>, V, Q: Ord + ?Sized, S: Vector<Item=(K, V)>> Index<&Q> for VectorMap<S> {
+    type Output = V;
+
+    #[inline]
+    fn index(&self, key: &Q) -> &V {
+        self.get(key).expect("key not found in map")
+    }
+}
+
+impl<K: Ord + Borrow<Q>, V, Q: Ord + ?Sized, S: Vector<Item=(K, V)>> IndexMut<&Q> for VectorMap<S> {
+    #[inline]
+    fn index_mut(&mut self, key: &Q) -> &mut V {
+        self.get_mut(key).expect("key not found in map")
+    }
+}
+
+impl<S: Vector + Clone> Clone for VectorMap<S> {
+    fn clone(&self) -> Self {
+        VectorMap {
+            vec: self.vec.clone(),
+        }
+    }
+}
+
+impl<K: Ord, V, S: Vector<Item=(K, V)>> Default for VectorMap<S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Ord + fmt::Debug, V: fmt::Debug, S: Vector<Item=(K, V)>> fmt::Debug for VectorMap<S> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<K: Ord + Hash, V: Hash, S: Vector<Item=(K, V)>> Hash for VectorMap<S> {
+    fn hash<H: Hasher>(&self, h: &mut H) {
+        self.as_slice().hash(h)
+    }
+}
+
+impl<K: Ord, V, S: Vector<Item=(K, V)>, T: Vector> PartialEq<VectorMap<T>> for VectorMap<S> where (K, V): PartialEq<T::Item> {
+    fn eq(&self, other: &VectorMap<T>) -> bool {
+        self.as_slice() == other.vec.as_slice()
+    }
+}
+
+impl<K: Ord, V: Eq, S: Vector<Item=(K, V)>> Eq for VectorMap<S> { }
@@ -0,0 +1,241 @@
+use core::fmt;
+use core::mem::replace;
+use core::borrow::Borrow;
+use core::ops::RangeBounds;
+use core::iter::FromIterator;
+use core::hash::{Hash, Hasher};
+use core::slice;
+use crate::util::sorted_range;
+use crate::{Array, ArrayVec, Vector, CapacityError};
+
+pub type ArraySet<A> = VectorSet<ArrayVec<A>>;
+#[cfg(feature = "alloc")]
+pub type SmallSet<A> = VectorSet<crate::SmallVec<A>>;
+
+/// A set stored as a sorted `Vector`, searched with a binary search.
+pub struct VectorSet<S: Vector> {
+    vec: S,
+}
+
+impl<T: Ord, S: Vector<Item=T>> VectorSet<S> {
+    #[inline]
+    pub fn new() -> Self {
+        VectorSet {
+            vec: S::new(),
+        }
+    }
+
+    #[inline]
+    pub fn with_capacity(cap: usize) -> Self {
+        VectorSet {
+            vec: S::with_capacity(cap),
+        }
+    }
+
+    #[inline]
+    pub fn into_inner(self) -> S {
+        self.vec
+    }
+
+    #[inline]
+    pub fn as_slice(&self) -> &[T] {
+        self.vec.as_slice()
+    }
+
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.vec.capacity()
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.vec.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.vec.is_empty()
+    }
+
+    #[inline]
+    pub fn clear(&mut self) {
+        self.vec.clear()
+    }
+
+    #[inline]
+    fn search<Q: Ord + ?Sized>(&self, value: &Q) -> Result<usize, usize> where T: Borrow<Q> {
+        self.vec.as_slice().binary_search_by(|v| v.borrow().cmp(value))
+    }
+
+    #[inline]
+    pub fn contains<Q: Ord + ?Sized>(&self, value: &Q) -> bool where T: Borrow<Q> {
+        self.search(value).is_ok()
+    }
+
+    #[inline]
+    pub fn get<Q: Ord + ?Sized>(&self, value: &Q) -> Option<&T> where T: Borrow<Q> {
+        match self.search(value) {
+            Ok(i) => Some(&self.vec.as_slice()[i]),
+            Err(..) => None,
+        }
+    }
+
+    /// Adds a value to the set, returning whether it was newly inserted.
+    pub fn insert(&mut self, value: T) -> bool {
+        match self.search(&value) {
+            Ok(..) => false,
+            Err(i) => {
+                self.vec.insert(i, value);
+                true
+            },
+        }
+    }
+
+    /// Adds a value to the set, returning the equal value it replaced.
+    pub fn replace(&mut self, value: T) -> Option<T> {
+        match self.search(&value) {
+            Ok(i) => Some(replace(&mut self.vec.as_mut_slice()[i], value)),
+            Err(i) => {
+                self.vec.insert(i, value);
+                None
+            },
+        }
+    }
+
+    #[inline]
+    pub fn remove<Q: Ord + ?Sized>(&mut self, value: &Q) -> bool where T: Borrow<Q> {
+        self.take(value).is_some()
+    }
+
+    pub fn take<Q: Ord + ?Sized>(&mut self, value: &Q) -> Option<T> where T: Borrow<Q> {
+        match self.search(value) {
+            Ok(i) => Some(self.vec.remove(i)),
+            Err(..) => None,
+        }
+    }
+
+    pub fn retain<F: FnMut(&T) -> bool>(&mut self, f: F) {
+        self.vec.retain(f)
+    }
+
+    #[inline]
+    pub fn first(&self) -> Option<&T> {
+        self.vec.as_slice().first()
+    }
+
+    #[inline]
+    pub fn last(&self) -> Option<&T> {
+        self.vec.as_slice().last()
+    }
+
+    #[inline]
+    pub fn pop_first(&mut self) -> Option<T> {
+        if self.is_empty() {
+            None
+        } else {
+            Some(self.vec.remove(0))
+        }
+    }
+
+    #[inline]
+    pub fn pop_last(&mut self) -> Option<T> {
+        self.vec.pop()
+    }
+
+    #[inline]
+    pub fn iter(&self) -> slice::Iter<'_, T> {
+        self.vec.as_slice().iter()
+    }
+
+    /// Iterates over the values that fall within `range`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the start of the range is greater than its end.
+    pub fn range<Q: Ord + ?Sized, R: RangeBounds<Q>>(&self, range: R) -> slice::Iter<'_, T> where T: Borrow<Q> {
+        let slice = self.vec.as_slice();
+        let (start, end) = sorted_range(slice, range, |v| v.borrow());
+        slice[start..end].iter()
+    }
+}
+
+impl<T: Ord, A: Array<Item=T>> VectorSet<ArrayVec<A>> {
+    /// Adds a value to the set, or returns it in the error when it is not
+    /// already present and the set is full.
+    pub fn try_insert(&mut self, value: T) -> Result<bool, CapacityError<T>> {
+        match self.search(&value) {
+            Ok(..) => Ok(false),
+            Err(i) => self.vec.try_insert(i, value).map(|()| true),
+        }
+    }
+}
+
+impl<T: Ord, S: Vector<Item=T> + IntoIterator<Item=T>> IntoIterator for VectorSet<S> {
+    type Item = T;
+    type IntoIter = S::IntoIter;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.vec.into_iter()
+    }
+}
+
+impl<'a, T: Ord + 'a, S: Vector<Item=T>> IntoIterator for &'a VectorSet<S> {
+    type Item = &'a T;
+    type IntoIter = slice::Iter<'a, T>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<T: Ord, S: Vector<Item=T>> Extend<T> for VectorSet<S> {
+    fn extend<I: IntoIterator<Item=T>>(&mut self, iter: I) {
+        for v in iter {
+            self.insert(v);
+        }
+    }
+}
+
+impl<T: Ord, S: Vector<Item=T>> FromIterator<T> for VectorSet<S> {
+    fn from_iter<I: IntoIterator<Item=T>>(iter: I) -> Self {
+        let mut set = Self::new();
+        set.extend(iter);
+        set
+    }
+}
+
+impl<S: Vector + Clone> Clone for VectorSet<S> {
+    fn clone(&self) -> Self {
+        VectorSet {
+            vec: self.vec.clone(),
+        }
+    }
+}
+
+impl<T: Ord, S: Vector<Item=T>> Default for VectorSet<S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Ord + fmt::Debug, S: Vector<Item=T>> fmt::Debug for VectorSet<S> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl<T: Ord + Hash, S: Vector<Item=T>> Hash for VectorSet<S> {
+    fn hash<H: Hasher>(&self, h: &mut H) {
+        self.as_slice().hash(h)
+    }
+}
+
+impl<T: Ord, S: Vector<Item=T>, U: Vector> PartialEq<VectorSet<U>> for VectorSet<S> where T: PartialEq<U::Item> {
+    fn eq(&self, other: &VectorSet<U>) -> bool {
+        self.as_slice() == other.vec.as_slice()
+    }
+}
+
+impl<T: Ord, S: Vector<Item=T>> Eq for VectorSet<S> { }
@@ -0,0 +1,98 @@
+use stack::{ArrayDeque, CapacityError};
+
+#[test]
+fn push_pop() {
+    let mut d = ArrayDeque::<[u32; 4]>::new();
+    d.push_back(1);
+    d.push_back(2);
+    d.push_front(0);
+    assert_eq!(d.len(), 3);
+    assert_eq!(d[0], 0);
+    assert_eq!(d.back(), Some(&2));
+    assert_eq!(d.pop_front(), Some(0));
+    assert_eq!(d.pop_back(), Some(2));
+    assert_eq!(d.pop_back(), Some(1));
+    assert_eq!(d.pop_back(), None);
+    assert!(d.is_empty());
+}
+
+#[test]
+fn policies() {
+    let mut d: ArrayDeque<[u32; 3]> = (0..3).collect();
+    assert!(d.is_full());
+    assert_eq!(d.try_push_back(3), Err(CapacityError::new(3)));
+    assert_eq!(d.try_push_front(3).unwrap_err().element(), 3);
+    assert_eq!(d.push_back_overwrite(3), Some(0));
+    assert_eq!(d.iter().cloned().collect::<Vec<_>>(), &[1, 2, 3]);
+    assert_eq!(d.push_front_overwrite(0), Some(3));
+    assert_eq!(d.iter().cloned().collect::<Vec<_>>(), &[0, 1, 2]);
+
+    let mut d = ArrayDeque::<[u32; 0]>::new();
+    assert_eq!(d.push_back_overwrite(1), Some(1));
+}
+
+#[test]
+#[should_panic]
+fn push_full() {
+    let mut d = ArrayDeque::from([0u8; 2]);
+    d.push_back(1);
+}
+
+#[test]
+fn wrapping() {
+    let mut d = ArrayDeque::<[String; 4]>::new();
+    for i in 0..4 {
+        d.push_back(i.to_string());
+    }
+    d.pop_front();
+    d.pop_front();
+    d.push_back("4".into());
+    d.push_back("5".into());
+    assert_eq!(d.as_slices(), (&["2".to_string(), "3".into()][..], &["4".to_string(), "5".into()][..]));
+    assert_eq!(d.iter().rev().map(|s| &s[..]).collect::<Vec<_>>(), &["5", "4", "3", "2"]);
+    for s in &mut d {
+        s.push('!');
+    }
+    assert_eq!(d.make_contiguous(), &["2!", "3!", "4!", "5!"]);
+    assert_eq!(d.as_slices().1.len(), 0);
+    assert_eq!(d.into_iter().rev().collect::<Vec<_>>(), &["5!", "4!", "3!", "2!"]);
+}
+
+#[test]
+fn drain() {
+    let mut d = ArrayDeque::<[Box<u32>; 6]>::new();
+    for i in 0..6 {
+        d.push_back(Box::new(i));
+    }
+    d.pop_front();
+    d.pop_front();
+    d.push_back(Box::new(6));
+    d.push_back(Box::new(7));
+    let drained: Vec<_> = d.drain(1..3).map(|v| *v).collect();
+    assert_eq!(drained, &[3, 4]);
+    assert_eq!(d.iter().map(|v| **v).collect::<Vec<_>>(), &[2, 5, 6, 7]);
+    d.drain(..1);
+    d.extend(vec![Box::new(8), Box::new(9)]);
+    assert_eq!(d.iter().map(|v| **v).collect::<Vec<_>>(), &[5, 6, 7, 8, 9]);
+    d.drain(..);
+    assert!(d.is_empty());
+}
+
+#[test]
+fn drop_items() {
+    use std::rc::Rc;
+
+    let rc = Rc::new(());
+    {
+        let mut d = ArrayDeque::<[Rc<()>; 3]>::new();
+        d.push_back(rc.clone());
+        d.push_back(rc.clone());
+        d.pop_front();
+        d.push_back(rc.clone());
+        d.push_back(rc.clone());
+        assert_eq!(Rc::strong_count(&rc), 4);
+        let c = d.clone();
+        assert_eq!(c, d);
+    }
+    assert_eq!(Rc::strong_count(&rc), 1);
+}
@@ -0,0 +1,69 @@
+use stack::{ArrayString, CapacityError};
+use std::fmt::Write;
+use std::collections::HashSet;
+
+#[test]
+fn test_push() {
+    let mut s = ArrayString::<[u8; 8]>::new();
+    s.push_str("abc");
+    s.push('é');
+    assert_eq!(s, "abcé");
+    assert_eq!(s.len(), 5);
+    assert_eq!(s.try_push_str("defg"), Err(CapacityError::new("defg")));
+    assert_eq!(s.try_push('ß'), Ok(()));
+    assert_eq!(s.try_push('ß').unwrap_err().element(), 'ß');
+    assert_eq!(s, "abcéß");
+    assert_eq!(s.pop(), Some('ß'));
+    assert_eq!(s.capacity(), 8);
+}
+
+#[test]
+#[should_panic]
+fn test_push_overflow() {
+    let mut s = ArrayString::<[u8; 2]>::new();
+    s.push_str("abc");
+}
+
+#[test]
+fn test_truncate() {
+    let mut s: ArrayString<[u8; 8]> = "aé".parse().unwrap();
+    s.truncate(3);
+    assert_eq!(s, "aé");
+    s.truncate(1);
+    assert_eq!(s, "a");
+}
+
+#[test]
+#[should_panic]
+fn test_truncate_boundary() {
+    let mut s: ArrayString<[u8; 8]> = "aé".parse().unwrap();
+    s.truncate(2);
+}
+
+#[test]
+fn test_write() {
+    let mut s = ArrayString::<[u8; 8]>::new();
+    write!(s, "{}-{}", 12, 34).unwrap();
+    assert_eq!(&*s, "12-34");
+    assert!(write!(s, "{}", 5678).is_err());
+    assert_eq!(format!("{}", s), s.as_str());
+}
+
+#[test]
+fn test_from_str() {
+    assert!("too long".parse::<ArrayString<[u8; 4]>>().is_err());
+    let s: ArrayString<[u8; 4]> = "ok".parse().unwrap();
+    assert_eq!(s, "ok");
+}
+
+#[test]
+fn test_borrow() {
+    let mut set = HashSet::new();
+    set.insert("key".parse::<ArrayString<[u8; 4]>>().unwrap());
+    assert!(set.contains("key"));
+    assert!(!set.contains("nope"));
+
+    let a: ArrayString<[u8; 4]> = "a".parse().unwrap();
+    let b: ArrayString<[u8; 8]> = "b".parse().unwrap();
+    assert!(a < b);
+}
@@ -8,6 +8,7 @@ use stack::{ArrayVec, Vector};
 use std::mem;
 
 #[test]
+#[allow(clippy::unnecessary_fold)]
 fn test_simple() {
     use std::ops::Add;
 
@@ -26,7 +27,7 @@ fn test_simple() {
 }
 
 #[test]
-fn test_u16_index() {
+fn test_large_len() {
     const N: usize = 4096;
     let mut vec: ArrayVec<[_; N]> = ArrayVec::new();
     for _ in 0..N {
@@ -36,6 +37,30 @@ fn test_u16_index() {
     assert_eq!(vec.len(), N);
 }
 
+#[test]
+fn test_compact_index() {
+    assert_eq!(mem::size_of::<ArrayVec<[u8; 4]>>(), 5);
+    assert_eq!(mem::size_of::<ArrayVec<[u8; 1000]>>(), 1002);
+    assert_eq!(mem::size_of::<ArrayVec<[u8; 0x10000]>>(), 0x10004);
+}
+
+#[cfg(feature = "unstable")]
+#[test]
+fn test_compact_index_any_len() {
+    assert_eq!(mem::size_of::<ArrayVec<[u16; 100_000]>>(), 200_004);
+}
+
+#[test]
+fn test_arbitrary_len() {
+    let mut vec: ArrayVec<[u8; 48]> = ArrayVec::new();
+    vec.extend(0..48);
+    assert!(vec.push_cap(48).is_err());
+    assert_eq!(vec.capacity(), 48);
+
+    let vec: ArrayVec<[u8; 1000]> = ArrayVec::new();
+    assert_eq!(vec.capacity(), 1000);
+}
+
 #[test]
 fn test_iter() {
     let mut iter = ArrayVec::from([1, 2, 3]).into_iter();
@@ -123,20 +148,28 @@ fn test_extend() {
 
 #[test]
 fn test_is_send_sync() {
-    let data = ArrayVec::<[Vec<i32>; 5]>::new();
+    let mut data = ArrayVec::<[Vec<i32>; 5]>::new();
     &data as &dyn Send;
     &data as &dyn Sync;
+
+    let drain = data.drain(..);
+    &drain as &dyn Send;
+    &drain as &dyn Sync;
+    drop(drain);
+
+    let splice = data.splice(.., None);
+    &splice as &dyn Send;
+    &splice as &dyn Sync;
 }
 
 #[test]
-#[cfg(feature = "drain")]
 fn test_drain() {
     let mut v = ArrayVec::from([0; 8]);
     v.pop();
     v.drain(0..7);
     assert_eq!(&v[..], &[]);
 
-    v.extend(0..);
+    v.extend(0..8);
     v.drain(1..4);
     assert_eq!(&v[..], &[0, 4, 5, 6, 7]);
     let u: ArrayVec<[_; 3]> = v.drain(1..4).rev().collect();
@@ -148,13 +181,40 @@ fn test_drain() {
 
 #[test]
 #[should_panic]
-#[cfg(feature = "drain")]
 fn test_drain_oob() {
     let mut v = ArrayVec::from([0; 8]);
     v.pop();
     v.drain(0..8);
 }
 
+#[test]
+fn test_drain_drop_writes() {
+    use std::cell::Cell;
+
+    // clears itself on drop, writing to the drained slot
+    struct Clearing<'a>(u32, &'a Cell<u32>);
+
+    impl<'a> Drop for Clearing<'a> {
+        fn drop(&mut self) {
+            self.1.set(self.1.get() + self.0);
+            self.0 = 0;
+        }
+    }
+
+    let dropped = Cell::new(0);
+    let mut v: ArrayVec<[Clearing; 6]> = (1..7).map(|i| Clearing(i, &dropped)).collect();
+    {
+        let mut drain = v.drain(1..5);
+        assert_eq!(drain.next().map(|c| c.0), Some(2));
+    }
+    assert_eq!(dropped.get(), 2 + 3 + 4 + 5);
+    assert_eq!(v.iter().map(|c| c.0).collect::<Vec<_>>(), [1, 6]);
+
+    v.splice(1..1, Some(Clearing(10, &dropped)));
+    v.drain(..1);
+    assert_eq!(v.iter().map(|c| c.0).collect::<Vec<_>>(), [10, 6]);
+}
+
 #[test]
 fn test_insert() {
     let mut v = ArrayVec::from([]);
@@ -210,3 +270,130 @@ fn test_into_inner_3() {
     v.extend(1..5);
     assert_eq!(v.into_inner().unwrap(), [1, 2, 3, 4]);
 }
+
+#[test]
+fn test_splice() {
+    let mut v = ArrayVec::<[_; 6]>::new();
+    v.extend(0..4);
+    let removed: ArrayVec<[_; 2]> = v.splice(1..3, 7..10).collect();
+    assert_eq!(&removed[..], &[1, 2]);
+    assert_eq!(&v[..], &[0, 7, 8, 9, 3]);
+}
+
+#[test]
+#[should_panic]
+fn test_splice_oob() {
+    let mut v = ArrayVec::from([0; 4]);
+    v.splice(1..2, 0..2);
+}
+
+#[test]
+fn test_split_off_append() {
+    let mut v = ArrayVec::from([0, 1, 2, 3]);
+    let mut tail = v.split_off(1);
+    assert_eq!(&v[..], &[0]);
+    assert_eq!(&tail[..], &[1, 2, 3]);
+    tail.pop();
+    v.append(&mut tail);
+    assert_eq!(&v[..], &[0, 1, 2]);
+    assert!(tail.is_empty());
+}
+
+#[test]
+fn test_try() {
+    use stack::CapacityError;
+
+    assert!(ArrayVec::<[u8; 2]>::try_with_capacity(3).is_err());
+    let mut v = ArrayVec::<[String; 3]>::try_with_capacity(2).unwrap();
+    assert_eq!(v.try_push("a".into()), Ok(()));
+    assert_eq!(v.try_insert(0, "b".into()), Ok(()));
+    assert_eq!(v.try_reserve(1), Ok(()));
+    assert_eq!(v.try_reserve(2), Err(CapacityError::new(())));
+    assert_eq!(v.try_extend_from_slice(&["c".into(), "d".into()]), Err(CapacityError::new(())));
+    assert_eq!(v.try_extend_from_slice(&["c".into()]), Ok(()));
+    assert_eq!(&v[..], &["b", "a", "c"]);
+
+    let err = v.try_push("d".into()).unwrap_err();
+    assert_eq!(err.to_string(), "insufficient capacity");
+    assert_eq!(err.element(), "d");
+    assert_eq!(v.try_insert(1, "e".into()).unwrap_err().element(), "e");
+    assert_eq!(v.len(), 3);
+
+    let _: &dyn std::error::Error = &CapacityError::new(0u8);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_into_vec() {
+    let mut v = ArrayVec::<[String; 4]>::new();
+    v.push("a".into());
+    v.push("b".into());
+    let boxed = v.clone().into_boxed_slice();
+    assert_eq!(&boxed[..], &["a", "b"]);
+    assert_eq!(v.clone().into_vec(), &["a", "b"]);
+    assert_eq!(Vec::from(v), &["a", "b"]);
+}
+
+#[test]
+fn test_format() {
+    use std::fmt::Write;
+
+    let v = stack::format_array!(16, "{}: {:04x}", "id", 0xbeef).unwrap();
+    assert_eq!(&v[..], b"id: beef");
+    assert!(stack::format_array!(4, "{}", 123456).is_err());
+
+    let mut v = ArrayVec::<[u8; 4]>::new();
+    assert!(write!(v, "ab").is_ok());
+    assert!(write!(v, "cde").is_err());
+    assert_eq!(&v[..], b"ab");
+}
+
+#[test]
+fn test_retain() {
+    let mut v: ArrayVec<[u32; 8]> = (0..8).collect();
+    v.retain(|&x| x % 3 != 0);
+    assert_eq!(&v[..], &[1, 2, 4, 5, 7]);
+
+    v.retain_mut(|x| {
+        *x *= 10;
+        *x != 40
+    });
+    assert_eq!(&v[..], &[10, 20, 50, 70]);
+}
+
+#[test]
+fn test_retain_panic() {
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+    use std::rc::Rc;
+
+    let item = Rc::new(());
+    let mut v: ArrayVec<[Rc<()>; 6]> = (0..6).map(|_| item.clone()).collect();
+    let mut calls = 0;
+    let res = catch_unwind(AssertUnwindSafe(|| v.retain(|_| {
+        calls += 1;
+        match calls {
+            1 => false,
+            4 => panic!(),
+            _ => true,
+        }
+    })));
+    assert!(res.is_err());
+    assert_eq!(v.len(), 5);
+    assert_eq!(Rc::strong_count(&item), 6);
+    drop(v);
+    assert_eq!(Rc::strong_count(&item), 1);
+}
+
+#[test]
+fn test_dedup() {
+    let mut v: ArrayVec<[u32; 8]> = [1, 1, 2, 3, 3, 3, 1, 4].iter().cloned().collect();
+    v.dedup();
+    assert_eq!(&v[..], &[1, 2, 3, 1, 4]);
+
+    v.dedup_by_key(|x| *x / 2);
+    assert_eq!(&v[..], &[1, 2, 1, 4]);
+
+    let mut v: ArrayVec<[&str; 4]> = ["a", "A", "b", "B"].iter().cloned().collect();
+    v.dedup_by(|a, b| a.eq_ignore_ascii_case(b));
+    assert_eq!(&v[..], &["a", "b"]);
+}
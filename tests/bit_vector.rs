@@ -0,0 +1,77 @@
+use stack::{BitArrayVec, CapacityError};
+#[cfg(feature = "alloc")]
+use stack::SmallBitVec;
+
+#[test]
+fn push_get_set() {
+    let mut v = BitArrayVec::<[usize; 2]>::new();
+    for i in 0..70 {
+        v.push(i % 3 == 0);
+    }
+    assert_eq!(v.len(), 70);
+    assert_eq!(v.get(3), Some(true));
+    assert_eq!(v.get(4), Some(false));
+    assert_eq!(v.get(70), None);
+    assert_eq!(v.count_ones(), 24);
+    v.set(4, true);
+    v.set(3, false);
+    assert_eq!(v.get(4), Some(true));
+    assert_eq!(v.count_ones(), 24);
+    assert_eq!(v.pop(), Some(true));
+    assert_eq!(v.pop(), Some(false));
+    assert_eq!(v.count_ones(), 23);
+    v.truncate(1);
+    assert_eq!(v.as_words(), &[1]);
+    assert_eq!(format!("{:?}", v), "1");
+}
+
+#[test]
+fn try_push() {
+    let mut v = BitArrayVec::<[usize; 1]>::new();
+    v.extend((0..usize::BITS).map(|_| true));
+    assert_eq!(v.capacity(), v.len());
+    assert_eq!(v.try_push(false), Err(CapacityError::new(false)));
+    v.clear();
+    assert!(v.none());
+    assert_eq!(v.try_push(true), Ok(()));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn set_ops() {
+    let a: SmallBitVec<[usize; 1]> = (0..100).map(|i| i % 2 == 0).collect();
+    let b: BitArrayVec<[usize; 2]> = (0..100).map(|i| i % 3 == 0).collect();
+
+    let mut u = a.clone();
+    u.union_with(&b);
+    assert_eq!(u.ones().take(5).collect::<Vec<_>>(), &[0, 2, 3, 4, 6]);
+
+    let mut i = a.clone();
+    i.intersect_with(&b);
+    assert_eq!(i.ones().collect::<Vec<_>>(), (0..100).step_by(6).collect::<Vec<_>>());
+
+    let mut d = a.clone();
+    d.difference_with(&b);
+    assert_eq!(d.count_ones(), 50 - 17);
+    assert!(d.iter().zip(&a).all(|(d, a)| !d || a));
+    assert_ne!(d, a);
+    assert_eq!(a, a.iter().collect::<BitArrayVec<[usize; 2]>>());
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+#[should_panic]
+fn set_ops_len() {
+    let mut a: SmallBitVec<[usize; 1]> = [true, false].iter().cloned().collect();
+    let b: SmallBitVec<[usize; 1]> = [true].iter().cloned().collect();
+    a.union_with(&b);
+}
+
+#[test]
+fn len_exceeds_word_count() {
+    let mut v = BitArrayVec::<[usize; 5]>::new();
+    v.extend((0..5 * usize::BITS).map(|i| i % 2 == 0));
+    assert_eq!(v.len(), 5 * usize::BITS as usize);
+    assert_eq!(v.count_ones(), v.len() / 2);
+    assert!(v.try_push(true).is_err());
+}
@@ -0,0 +1,78 @@
+use stack::{ArrayVec, DstStack};
+#[cfg(feature = "alloc")]
+use stack::SmallVec;
+use std::cell::RefCell;
+use std::fmt::Debug;
+
+#[test]
+fn push_pop() {
+    let mut stack = DstStack::<dyn Debug>::new();
+    stack.push(1u8);
+    stack.push(String::from("two"));
+    stack.push([3u64; 3]);
+    assert_eq!(stack.len(), 3);
+    assert_eq!(format!("{:?}", stack), "[1, \"two\", [3, 3, 3]]");
+    assert_eq!(format!("{:?}", stack.last()), "Some([3, 3, 3])");
+
+    let top = stack.pop().unwrap();
+    assert_eq!(format!("{:?}", top), "[3, 3, 3]");
+    assert_eq!(format!("{:?}", stack.pop().unwrap()), "\"two\"");
+    assert_eq!(stack.len(), 1);
+    stack.push(4i32);
+    assert_eq!(format!("{:?}", stack), "[1, 4]");
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn iter_mut() {
+    let mut stack = DstStack::<[u32], Vec<usize>>::new();
+    stack.push([1, 2]);
+    stack.push([3]);
+    stack.push([4, 5, 6]);
+    for v in &mut stack {
+        v.reverse();
+    }
+    let all: Vec<_> = stack.iter().flat_map(|v| v.iter().cloned()).collect();
+    assert_eq!(all, &[2, 1, 3, 6, 5, 4]);
+    assert_eq!(stack.iter().len(), 3);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn closures() {
+    let out = RefCell::new(Vec::new());
+    let mut stack = DstStack::<dyn Fn(u32), SmallVec<[usize; 8]>>::new();
+    for i in 0..4 {
+        let out = &out;
+        stack.push(move |x| out.borrow_mut().push(x * i));
+    }
+    for f in &stack {
+        f(2);
+    }
+    assert_eq!(*out.borrow(), &[0, 2, 4, 6]);
+}
+
+#[test]
+fn drop_order() {
+    struct Log<'a>(&'a RefCell<Vec<u32>>, u32);
+    impl<'a> Drop for Log<'a> { fn drop(&mut self) { self.0.borrow_mut().push(self.1) } }
+
+    let log = RefCell::new(Vec::new());
+    {
+        let mut stack = DstStack::<dyn Fn()>::new();
+        for i in 0..3 {
+            let entry = Log(&log, i);
+            stack.push(move || assert!(entry.1 < 3));
+        }
+        drop(stack.pop());
+    }
+    assert_eq!(*log.borrow(), &[2, 0, 1]);
+}
+
+#[test]
+#[should_panic]
+fn overflow() {
+    let mut stack = DstStack::<dyn Debug, ArrayVec<[usize; 4]>>::new();
+    stack.push(0u8);
+    stack.push(0u8);
+}
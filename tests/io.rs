@@ -0,0 +1,58 @@
+#![cfg(feature = "std")]
+
+use std::io::{self, Cursor, Read, Write, BufRead, Seek, SeekFrom};
+use std::iter::FromIterator;
+use stack::{ArrayVec, SmallVec, Vector};
+
+#[test]
+fn test_array_write() {
+    let mut v = ArrayVec::<[u8; 4]>::new();
+    assert_eq!(v.write(b"abc").unwrap(), 3);
+    assert_eq!(v.write(b"def").unwrap(), 1);
+    assert_eq!(v.write(b"g").unwrap(), 0);
+    assert_eq!(&v[..], b"abcd");
+
+    let mut v = ArrayVec::<[u8; 4]>::new();
+    let err = v.write_all(b"hello").unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::WriteZero);
+    assert_eq!(&v[..], b"hell");
+
+    let mut v = ArrayVec::<[u8; 8]>::new();
+    write!(v, "{}-{}", 12, 34).unwrap();
+    assert_eq!(&v[..], b"12-34");
+}
+
+#[test]
+fn test_small_write() {
+    let mut v = SmallVec::<[u8; 4]>::new();
+    v.write_all(b"ab").unwrap();
+    assert!(!v.is_spilled());
+    assert_eq!(v.write(b"cdefg").unwrap(), 5);
+    assert!(v.is_spilled());
+    assert_eq!(&v[..], b"abcdefg");
+}
+
+#[test]
+fn test_cursor() {
+    let mut packet = ArrayVec::<[u8; 16]>::new();
+    packet.write_all(b"head\nbody").unwrap();
+
+    let mut cursor = Cursor::new(packet);
+    let mut line = String::new();
+    cursor.read_line(&mut line).unwrap();
+    assert_eq!(line, "head\n");
+
+    let mut rest = [0u8; 8];
+    assert_eq!(cursor.read(&mut rest).unwrap(), 4);
+    assert_eq!(&rest[..4], b"body");
+
+    cursor.seek(SeekFrom::Start(1)).unwrap();
+    let mut byte = [0u8];
+    cursor.read_exact(&mut byte).unwrap();
+    assert_eq!(&byte, b"e");
+
+    let mut cursor = Cursor::new(SmallVec::<[u8; 2]>::from_iter(b"spilled".iter().cloned()));
+    let mut s = String::new();
+    cursor.read_to_string(&mut s).unwrap();
+    assert_eq!(s, "spilled");
+}
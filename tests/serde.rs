@@ -0,0 +1,69 @@
+#![cfg(feature = "serde")]
+
+use serde::{Deserialize, Deserializer};
+use serde_test::{Token, assert_tokens, assert_ser_tokens, assert_de_tokens, assert_de_tokens_error};
+use stack::{ArrayVec, SmallVec};
+
+// `SmallVec` has no `PartialEq`, so compare contents and spill state.
+#[derive(Debug)]
+struct Small(SmallVec<[u8; 2]>);
+
+impl PartialEq for Small {
+    fn eq(&self, other: &Self) -> bool {
+        self.0[..] == other.0[..] && self.0.is_spilled() == other.0.is_spilled()
+    }
+}
+
+impl<'de> Deserialize<'de> for Small {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        SmallVec::deserialize(deserializer).map(Small)
+    }
+}
+
+#[test]
+fn test_array_vec() {
+    let v: ArrayVec<[u8; 4]> = (1..4).collect();
+    assert_tokens(&v, &[
+        Token::Seq { len: Some(3) },
+        Token::U8(1),
+        Token::U8(2),
+        Token::U8(3),
+        Token::SeqEnd,
+    ]);
+}
+
+#[test]
+fn test_array_vec_capacity() {
+    assert_de_tokens_error::<ArrayVec<[u8; 2]>>(&[
+        Token::Seq { len: Some(3) },
+    ], "insufficient capacity");
+
+    assert_de_tokens_error::<ArrayVec<[u8; 2]>>(&[
+        Token::Seq { len: None },
+        Token::U8(1),
+        Token::U8(2),
+        Token::U8(3),
+    ], "insufficient capacity");
+}
+
+#[test]
+fn test_small_vec() {
+    let v: SmallVec<[u8; 2]> = (1..3).collect();
+    assert!(!v.is_spilled());
+    assert_ser_tokens(&v, &[
+        Token::Seq { len: Some(2) },
+        Token::U8(1),
+        Token::U8(2),
+        Token::SeqEnd,
+    ]);
+
+    let v: SmallVec<[u8; 2]> = (1..4).collect();
+    assert!(v.is_spilled());
+    assert_de_tokens(&Small(v), &[
+        Token::Seq { len: None },
+        Token::U8(1),
+        Token::U8(2),
+        Token::U8(3),
+        Token::SeqEnd,
+    ]);
+}
@@ -0,0 +1,99 @@
+#![cfg(feature = "alloc")]
+use stack::{ArrayVec, SmallBox, small_box};
+use std::cell::Cell;
+use std::fmt::Debug;
+
+struct Bump<'a>(&'a Cell<usize>);
+
+impl<'a> Drop for Bump<'a> {
+    fn drop(&mut self) {
+        self.0.set(self.0.get() + 1);
+    }
+}
+
+#[test]
+fn inline() {
+    let v: SmallBox<dyn Debug> = small_box!(1234u32);
+    assert!(v.is_inline());
+    assert_eq!(format!("{:?}", v), "1234");
+}
+
+#[test]
+fn boxed() {
+    let v: SmallBox<dyn Debug, ArrayVec<[usize; 4]>> = small_box!([1u64; 8]);
+    assert!(!v.is_inline());
+    assert_eq!(format!("{:?}", v), "[1, 1, 1, 1, 1, 1, 1, 1]");
+}
+
+#[test]
+#[should_panic]
+fn mismatched_ref_boxed() {
+    let a = [0u8; 64];
+    let _: SmallBox<[u8], ArrayVec<[usize; 4]>> = unsafe { SmallBox::new_ref(&a[..2], &a) };
+}
+
+#[test]
+fn closure() {
+    let x = [7u64; 16];
+    let small: SmallBox<dyn Fn() -> u64> = small_box!(move || x[0]);
+    assert!(!small.is_inline());
+    assert_eq!(small(), 7);
+
+    let small: SmallBox<dyn Fn() -> u64> = small_box!(|| 3u64);
+    assert!(small.is_inline());
+    assert_eq!(small(), 3);
+}
+
+#[test]
+fn into_box() {
+    let flag = Cell::new(0);
+    let v: SmallBox<dyn Fn() -> usize> = {
+        let b = Bump(&flag);
+        small_box!(move || b.0.get())
+    };
+    assert!(v.is_inline());
+    let b: Box<dyn Fn() -> usize> = v.into_box();
+    assert_eq!(flag.get(), 0);
+    assert_eq!(b(), 0);
+    drop(b);
+    assert_eq!(flag.get(), 1);
+
+    let v: SmallBox<dyn Debug> = SmallBox::from(Box::new(5u8) as Box<dyn Debug>);
+    assert!(!v.is_inline());
+    assert_eq!(format!("{:?}", v.into_box()), "5");
+}
+
+#[test]
+fn drop_boxed() {
+    let flag = Cell::new(0);
+    let v: SmallBox<dyn Fn() -> usize, ArrayVec<[usize; 2]>> = {
+        let b = Bump(&flag);
+        let pad = [0usize; 4];
+        small_box!(move || b.0.get() + pad[0])
+    };
+    assert!(!v.is_inline());
+    drop(v);
+    assert_eq!(flag.get(), 1);
+}
+
+#[test]
+fn over_aligned() {
+    #[derive(Debug)]
+    #[allow(dead_code)]
+    #[repr(align(64))]
+    struct Aligned(u8);
+
+    let v: SmallBox<dyn Debug> = small_box!(Aligned(1));
+    assert!(!v.is_inline());
+    assert_eq!(&*v as *const dyn Debug as *const u8 as usize % 64, 0);
+    assert_eq!(format!("{:?}", v), "Aligned(1)");
+}
+
+#[test]
+fn coerce() {
+    let v: SmallBox<dyn Debug> = SmallBox::new(5u8);
+    assert!(v.is_inline());
+    let v: SmallBox<[u64]> = SmallBox::new([1u64; 16]);
+    assert!(!v.is_inline());
+    assert_eq!(v.len(), 16);
+}
@@ -0,0 +1,49 @@
+#![cfg(feature = "alloc")]
+use stack::SmallDeque;
+
+#[test]
+fn spill() {
+    let mut d = SmallDeque::<[u32; 4]>::new();
+    for i in 0..4 {
+        d.push_back(i);
+    }
+    assert!(!d.is_spilled());
+    d.push_front(10);
+    assert!(d.is_spilled());
+    assert_eq!(d.iter().cloned().collect::<Vec<_>>(), &[10, 0, 1, 2, 3]);
+    assert_eq!(d[0], 10);
+    assert_eq!(d.pop_back(), Some(3));
+    assert_eq!(d.pop_front(), Some(10));
+
+    d.shrink_to_fit();
+    assert!(!d.is_spilled());
+    assert_eq!(d.iter().rev().cloned().collect::<Vec<_>>(), &[2, 1, 0]);
+
+    d.spill();
+    assert!(d.is_spilled());
+    assert_eq!(d.len(), 3);
+}
+
+#[test]
+fn wrapped_spill() {
+    let mut d = SmallDeque::<[String; 3]>::new();
+    d.push_back("b".into());
+    d.push_back("c".into());
+    d.push_front("a".into());
+    assert!(!d.is_spilled());
+    d.push_back("d".into());
+    assert!(d.is_spilled());
+    assert_eq!(d.into_iter().collect::<Vec<_>>(), &["a", "b", "c", "d"]);
+}
+
+#[test]
+fn drain() {
+    let mut d: SmallDeque<[u32; 8]> = (0..6).collect();
+    assert_eq!(d.drain(1..3).collect::<Vec<_>>(), &[1, 2]);
+    assert_eq!(d.iter().cloned().collect::<Vec<_>>(), &[0, 3, 4, 5]);
+
+    let mut d: SmallDeque<[u32; 2]> = (0..6).collect();
+    assert!(d.is_spilled());
+    assert_eq!(d.drain(..4).rev().collect::<Vec<_>>(), &[3, 2, 1, 0]);
+    assert_eq!(d, (4..6).collect::<SmallDeque<[u32; 4]>>());
+}
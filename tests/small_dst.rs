@@ -4,7 +4,9 @@
  * of the MIT license. See the COPYING file for details.
  */
 
-use stack::{SmallVec, ArrayVec, SmallDST, small_dst};
+use stack::{ArrayVec, SmallDST, small_dst};
+#[cfg(feature = "alloc")]
+use stack::SmallVec;
 
 #[test]
 // A trivial check that ensures that methods are correctly called
@@ -80,6 +82,7 @@ fn oversize() {
     let _: SmallDST<dyn Any, ArrayVec<[usize; 8]>> = small_dst!([0usize; 8]);
 }
 
+#[cfg(feature = "alloc")]
 #[test]
 fn spill() {
     use std::any::Any;
@@ -94,3 +97,168 @@ fn sized() {
     let value = SmallDST::<_, ArrayVec<[usize; 1]>>::from(12345usize);
     assert_eq!(*value, 12345);
 }
+
+#[test]
+fn over_aligned() {
+    use std::fmt::Debug;
+
+    #[derive(Debug)]
+    #[allow(dead_code)]
+    #[repr(align(32))]
+    struct Aligned(u8);
+
+    #[allow(dead_code)]
+    #[repr(align(32))]
+    struct Word([u8; 32]);
+
+    fn check(dst: &SmallDST<dyn Debug, ArrayVec<[Word; 4]>>) {
+        assert_eq!(&**dst as *const dyn Debug as *const u8 as usize % 32, 0);
+        assert_eq!(format!("{:?}", dst), "Aligned(7)");
+    }
+
+    let dst: SmallDST<dyn Debug, ArrayVec<[Word; 4]>> = small_dst!(Aligned(7));
+    check(&dst);
+    let moved = Some(dst);
+    check(moved.as_ref().unwrap());
+
+    let bytes: SmallDST<dyn Debug, ArrayVec<[u8; 32]>> = small_dst!(5u8);
+    assert_eq!(format!("{:?}", bytes), "5");
+}
+
+#[test]
+#[should_panic]
+fn over_aligned_default() {
+    use std::fmt::Debug;
+
+    #[derive(Debug)]
+    #[allow(dead_code)]
+    #[repr(align(32))]
+    struct Aligned(u8);
+
+    let _: SmallDST<dyn Debug> = small_dst!(Aligned(7));
+}
+
+#[test]
+fn coerce() {
+    use std::any::Any;
+    use std::fmt::Debug;
+
+    let v: SmallDST<dyn Debug> = SmallDST::new(1234u32);
+    assert_eq!(format!("{:?}", v), "1234");
+
+    let v: SmallDST<dyn Any + Send> = SmallDST::new(String::from("any"));
+    assert!(v.is::<String>());
+
+    let v: SmallDST<[u8]> = SmallDST::new([1u8, 2, 3]);
+    assert_eq!(&*v, &[1, 2, 3]);
+
+    let x = 5;
+    let v: SmallDST<dyn Fn(u32, u32) -> u32> = SmallDST::new(move |a, b| a + b + x);
+    assert_eq!(v(1, 2), 8);
+
+    let mut count = 0;
+    {
+        let mut v: SmallDST<dyn FnMut()> = SmallDST::new(|| count += 1);
+        v();
+        v();
+    }
+    assert_eq!(count, 2);
+}
+
+#[test]
+fn from_str() {
+    let v: SmallDST<str> = "hello".into();
+    assert_eq!(&*v, "hello");
+
+    let v: SmallDST<str, ArrayVec<[u8; 24]>> = "inline bytes".into();
+    assert_eq!(&*v, "inline bytes");
+}
+
+#[test]
+#[should_panic]
+fn mismatched_ref() {
+    let a = [0u8; 4];
+    let _: SmallDST<[u8]> = unsafe { SmallDST::new_ref(&a[..2], &a) };
+}
+
+struct Bump<'a>(&'a std::cell::Cell<usize>);
+impl<'a> Drop for Bump<'a> { fn drop(&mut self) { self.0.set(self.0.get() + 1); } }
+
+#[test]
+fn call_once() {
+    use std::cell::Cell;
+
+    let f: SmallDST<dyn FnOnce() + Send> = SmallDST::new(|| ());
+    f.call_once();
+
+    let flag = Cell::new(0);
+    let bump = Bump(&flag);
+    let s = String::from("new");
+    let f: SmallDST<dyn FnOnce(char, usize) -> String> = SmallDST::new(move |sep: char, n: usize| {
+        let _bump = bump;
+        vec![&s[..]; n].join(&sep.to_string())
+    });
+    assert_eq!(f.call_once('-', 3), "new-new-new");
+    assert_eq!(flag.get(), 1);
+
+    let flag = Cell::new(0);
+    let bump = Bump(&flag);
+    let f: SmallDST<dyn FnOnce()> = small_dst!(move || drop(bump));
+    drop(f);
+    assert_eq!(flag.get(), 1);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn call_once_boxed() {
+    use std::cell::Cell;
+
+    let flag = Cell::new(0);
+    let bump = Bump(&flag);
+    let s = String::from("owned");
+    let f: SmallDST<dyn FnOnce(usize) -> String> = small_dst!(move |n: usize| {
+        let _bump = bump;
+        s.repeat(n)
+    });
+    assert_eq!(f.call_once(2), "ownedowned");
+    assert_eq!(flag.get(), 1);
+
+    // Fills the storage, leaving no room to record a call shim.
+    let words = [1usize; 8];
+    let f: SmallDST<dyn FnOnce() -> usize> = SmallDST::new(move || words.iter().sum());
+    assert_eq!(f.call_once(), 8);
+}
+
+#[cfg(not(feature = "alloc"))]
+#[test]
+#[should_panic]
+fn call_once_unboxed() {
+    let f: SmallDST<dyn FnOnce()> = small_dst!(|| ());
+    f.call_once();
+}
+
+#[test]
+fn downcast() {
+    use std::any::Any;
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    let mut v: SmallDST<dyn Any> = SmallDST::new(String::from("message"));
+    assert!(v.is::<String>());
+    assert!(v.downcast_ref::<u32>().is_none());
+    v.downcast_mut::<String>().unwrap().push('!');
+    let v = v.downcast::<u32>().unwrap_err();
+    assert_eq!(v.downcast::<String>().unwrap(), "message!");
+
+    struct Bump(Rc<Cell<usize>>);
+    impl Drop for Bump { fn drop(&mut self) { self.0.set(self.0.get() + 1); } }
+
+    let flag = Rc::new(Cell::new(0));
+    let v: SmallDST<dyn Any + Send + Sync, ArrayVec<[usize; 4]>> = SmallDST::new(7u64);
+    assert_eq!(v.downcast_ref::<u64>(), Some(&7));
+    let v: SmallDST<dyn Any> = SmallDST::new(Bump(flag.clone()));
+    let bump = v.downcast::<Bump>().ok().unwrap();
+    assert_eq!(flag.get(), 0);
+    drop(bump);
+    assert_eq!(flag.get(), 1);
+}
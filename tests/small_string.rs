@@ -0,0 +1,53 @@
+#![cfg(feature = "alloc")]
+use stack::SmallString;
+use std::fmt::Write;
+
+#[test]
+fn test_inline() {
+    let mut s = SmallString::<[u8; 8]>::new();
+    s.push_str("abc");
+    s.push('d');
+    assert_eq!(s, "abcd");
+    assert!(!s.is_spilled());
+    assert_eq!(s.capacity(), 8);
+    assert_eq!(s.pop(), Some('d'));
+    assert_eq!(s.into_string(), "abc");
+}
+
+#[test]
+fn test_spill() {
+    let mut s = SmallString::<[u8; 4]>::from("abc");
+    assert!(!s.is_spilled());
+    write!(s, "{}", 1234).unwrap();
+    assert!(s.is_spilled());
+    assert_eq!(s, "abc1234");
+    s.truncate(2);
+    assert_eq!(s.as_str(), "ab");
+
+    let mut s = SmallString::<[u8; 4]>::from("ab");
+    s.spill();
+    assert!(s.is_spilled());
+    assert_eq!(s, "ab");
+}
+
+#[test]
+fn test_into_string() {
+    let s = String::with_capacity(32) + "hello";
+    let ptr = s.as_ptr();
+    let s = SmallString::<[u8; 8]>::from(s);
+    assert!(s.is_spilled());
+    let s = s.into_string();
+    assert_eq!(s.as_ptr(), ptr);
+    assert_eq!(s, "hello");
+}
+
+#[test]
+fn test_cmp() {
+    let a: SmallString<[u8; 2]> = "abc".parse().unwrap();
+    let b: SmallString<[u8; 8]> = "abc".into();
+    assert_eq!(a, b);
+    let c: SmallString<[u8; 4]> = "abd".into();
+    assert!(a < c);
+    assert!(a < *"abd");
+    assert_eq!(format!("{:?}", a), "\"abc\"");
+}
@@ -2,6 +2,9 @@
  * License, v. 2.0. If a copy of the MPL was not distributed with this
  * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
 
+#![cfg(feature = "alloc")]
+#![cfg_attr(feature = "unstable", feature(allocator_api))]
+
 use stack::{ArrayVec, SmallVec, Vector};
 
 #[test]
@@ -116,3 +119,211 @@ fn test_truncate() {
 
     assert_eq!(&v.iter().map(|v| **v).collect::<Vec<_>>(), &[0, 3, 2]);
 }
+
+#[test]
+fn test_drain() {
+    let mut v: SmallVec<[Box<u8>; 2]> = (0..6).map(Box::new).collect();
+    assert!(v.is_spilled());
+    let drained: Vec<_> = v.drain(1..4).map(|v| *v).collect();
+    assert_eq!(drained, &[1, 2, 3]);
+    assert_eq!(v.iter().map(|v| **v).collect::<Vec<_>>(), &[0, 4, 5]);
+
+    let mut v: SmallVec<[Box<u8>; 4]> = (0..4).map(Box::new).collect();
+    assert!(!v.is_spilled());
+    {
+        let mut drain = v.drain(..3);
+        assert_eq!(drain.len(), 3);
+        assert_eq!(drain.next_back().map(|v| *v), Some(2));
+    }
+    assert_eq!(v.iter().map(|v| **v).collect::<Vec<_>>(), &[3]);
+}
+
+#[test]
+fn test_drain_forget() {
+    let mut v: SmallVec<[String; 4]> = Vector::new();
+    v.push("a".into());
+    v.push("b".into());
+    v.push("c".into());
+    std::mem::forget(v.drain(1..2));
+    assert_eq!(&*v, &[String::from("a")][..]);
+}
+
+#[test]
+fn test_drain_panic() {
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+
+    struct PanicOnDrop(bool);
+
+    impl Drop for PanicOnDrop {
+        fn drop(&mut self) {
+            if self.0 {
+                panic!("drop");
+            }
+        }
+    }
+
+    let mut v: SmallVec<[PanicOnDrop; 4]> = Vector::new();
+    v.push(PanicOnDrop(false));
+    v.push(PanicOnDrop(true));
+    v.push(PanicOnDrop(false));
+    v.push(PanicOnDrop(false));
+    assert!(catch_unwind(AssertUnwindSafe(|| drop(v.drain(..2)))).is_err());
+    assert_eq!(v.len(), 2);
+}
+
+#[test]
+fn test_splice() {
+    let mut v: SmallVec<[String; 4]> = ["a", "b", "c", "d"].iter().map(|&s| s.into()).collect();
+    let removed: Vec<_> = v.splice(1..3, vec!["x".into(), "y".into(), "z".into()]).collect();
+    assert_eq!(removed, &["b", "c"]);
+    assert!(v.is_spilled());
+    assert_eq!(&*v, &["a", "x", "y", "z", "d"]);
+
+    let mut v: SmallVec<[u8; 8]> = (0..4).collect();
+    v.splice(1..3, (0..10).filter(|&v| v > 7));
+    assert_eq!(&*v, &[0, 8, 9, 3]);
+    v.splice(4.., 4..6);
+    assert_eq!(&*v, &[0, 8, 9, 3, 4, 5]);
+    v.splice(..2, None);
+    assert_eq!(&*v, &[9, 3, 4, 5]);
+}
+
+#[test]
+fn test_split_off_append() {
+    let mut v: SmallVec<[u8; 4]> = (0..6).collect();
+    let mut tail = v.split_off(2);
+    assert_eq!(&*v, &[0, 1]);
+    assert_eq!(&*tail, &[2, 3, 4, 5]);
+    assert!(!tail.is_spilled());
+
+    v.append(&mut tail);
+    assert!(tail.is_empty());
+    assert!(v.is_spilled());
+    assert_eq!(&*v, &[0, 1, 2, 3, 4, 5]);
+}
+
+#[test]
+fn test_unspill() {
+    let mut v: SmallVec<[String; 2]> = Vector::new();
+    v.push("a".into());
+    v.push("b".into());
+    v.push("c".into());
+    assert!(v.is_spilled());
+    assert!(!v.unspill());
+
+    v.pop();
+    Vector::shrink_to_fit(&mut v);
+    assert!(v.is_spilled());
+    v.shrink_to_inline();
+    assert!(!v.is_spilled());
+    assert_eq!(v.capacity(), 2);
+    assert_eq!(&*v, &[String::from("a"), String::from("b")][..]);
+
+    assert!(v.unspill());
+}
+
+#[test]
+fn test_into_vec() {
+    let v: SmallVec<[String; 4]> = vec!["a".into(), "b".into()].into_iter().collect();
+    assert!(!v.is_spilled());
+    assert_eq!(&v.into_boxed_slice()[..], &["a", "b"]);
+
+    let v: SmallVec<[u8; 2]> = (0..4).collect();
+    assert!(v.is_spilled());
+    let ptr = v.as_ptr();
+    let v = v.into_vec();
+    assert_eq!(v.as_ptr(), ptr);
+    assert_eq!(v, &[0, 1, 2, 3]);
+}
+
+#[test]
+fn test_dedup_spilled() {
+    let mut v: SmallVec<[u8; 2]> = [1, 1, 2, 2, 3].iter().cloned().collect();
+    assert!(v.is_spilled());
+    v.dedup();
+    assert_eq!(&v[..], &[1, 2, 3]);
+    v.retain(|&x| x != 2);
+    assert_eq!(&v[..], &[1, 3]);
+}
+
+#[test]
+fn test_format() {
+    use std::fmt::Write;
+
+    let v = stack::format_small!(4, "{}-{}", 1, 2);
+    assert!(!v.is_spilled());
+    assert_eq!(&v[..], b"1-2");
+
+    let mut v = stack::format_small!(4, "{:?}", "spilled");
+    assert!(v.is_spilled());
+    write!(v, "!").unwrap();
+    assert_eq!(&v[..], b"\"spilled\"!");
+}
+
+#[cfg(feature = "unstable")]
+#[test]
+fn test_spill_allocator() {
+    use std::alloc::{AllocError, Allocator, Global, Layout};
+    use std::ptr::NonNull;
+    use std::cell::Cell;
+    use std::rc::Rc;
+    use stack::VectorAllocator;
+
+    #[derive(Clone, Default)]
+    struct Counting(Rc<Cell<usize>>);
+
+    impl VectorAllocator for Counting { }
+
+    unsafe impl Allocator for Counting {
+        fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+            self.0.set(self.0.get() + 1);
+            Global.allocate(layout)
+        }
+
+        unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+            Global.deallocate(ptr, layout)
+        }
+    }
+
+    let alloc = Counting::default();
+    let allocations = || alloc.0.get();
+
+    let mut v = SmallVec::<[u32; 2], Vec<u32, Counting>>::new_in(alloc.clone());
+    v.push(1);
+    v.push(2);
+    assert!(!v.is_spilled());
+    assert_eq!(allocations(), 0);
+
+    v.push(3);
+    assert!(v.is_spilled());
+    assert!(allocations() > 0);
+    assert_eq!(&v[..], &[1, 2, 3][..]);
+
+    let before = allocations();
+    v.reserve(64);
+    assert!(allocations() > before);
+
+    v.truncate(2);
+    v.shrink_to_inline();
+    assert!(!v.is_spilled());
+
+    let before = allocations();
+    v.extend(0..3);
+    assert!(v.is_spilled());
+    assert!(allocations() > before);
+
+    let before = allocations();
+    let mut v = SmallVec::<[u32; 2], Vec<u32, Counting>>::with_capacity_in(8, alloc.clone());
+    assert!(v.is_spilled());
+    assert_eq!(allocations(), before + 1);
+
+    v.extend(0..5);
+    assert_eq!(v.drain(1..3).collect::<Vec<_>>(), [1, 2]);
+    assert_eq!(v.into_vec(), [0, 3, 4]);
+
+    let before = allocations();
+    let mut v = SmallVec::<[u32; 2], Vec<u32, Counting>>::new();
+    v.extend(0..3);
+    assert!(v.is_spilled());
+    assert_eq!(allocations(), before);
+}
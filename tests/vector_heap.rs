@@ -0,0 +1,49 @@
+use stack::{ArrayBinaryHeap, Min, CapacityError};
+#[cfg(feature = "alloc")]
+use stack::{SmallBinaryHeap, VectorHeap, PeekMut};
+
+#[test]
+fn push_pop() {
+    let mut h = ArrayBinaryHeap::<[u32; 8]>::new();
+    for &i in &[3, 1, 4, 1, 5, 9, 2, 6] {
+        h.push(i);
+    }
+    assert_eq!(h.peek(), Some(&9));
+    assert_eq!(h.try_push(7), Err(CapacityError::new(7)));
+    let mut out = Vec::new();
+    while let Some(i) = h.pop() {
+        out.push(i);
+    }
+    assert_eq!(out, &[9, 6, 5, 4, 3, 2, 1, 1]);
+}
+
+#[test]
+fn min() {
+    let mut h: ArrayBinaryHeap<[u32; 8], Min> = [5, 3, 8, 1].iter().cloned().collect();
+    assert_eq!(h.try_push(0), Ok(()));
+    assert_eq!(h.pop(), Some(0));
+    assert_eq!(h.pop(), Some(1));
+    assert_eq!(&h.into_sorted_vec()[..], &[8, 5, 3]);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn peek_mut() {
+    let mut h: SmallBinaryHeap<[u32; 2]> = (0..5).collect();
+    *h.peek_mut().unwrap() = 0;
+    assert_eq!(h.peek(), Some(&3));
+    assert_eq!(PeekMut::pop(h.peek_mut().unwrap()), 3);
+    assert_eq!(&h.into_sorted_vec()[..], &[0, 0, 1, 2]);
+
+    let mut h = VectorHeap::<Vec<u32>>::new();
+    assert!(h.peek_mut().is_none());
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn from_vec() {
+    let h = VectorHeap::<_, Min>::from(vec![9, 2, 7, 4, 4, 1]);
+    assert_eq!(h.len(), 6);
+    assert_eq!(h.peek(), Some(&1));
+    assert_eq!(h.into_sorted_vec(), &[9, 7, 4, 4, 2, 1]);
+}
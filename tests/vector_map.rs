@@ -0,0 +1,79 @@
+use stack::{ArrayMap, CapacityError};
+#[cfg(feature = "alloc")]
+use stack::{SmallMap, Entry};
+
+#[test]
+fn insert_remove() {
+    let mut m = ArrayMap::<[(u32, &str); 4]>::new();
+    assert_eq!(m.insert(3, "c"), None);
+    assert_eq!(m.insert(1, "a"), None);
+    assert_eq!(m.insert(2, "b"), None);
+    assert_eq!(m.insert(1, "A"), Some("a"));
+    assert_eq!(m.len(), 3);
+    assert_eq!(m.get(&1), Some(&"A"));
+    assert_eq!(m[&2], "b");
+    assert!(!m.contains_key(&4));
+    assert_eq!(m.keys().cloned().collect::<Vec<_>>(), &[1, 2, 3]);
+    assert_eq!(m.remove(&2), Some("b"));
+    assert_eq!(m.remove(&2), None);
+    assert_eq!(m.first_key_value(), Some((&1, &"A")));
+    assert_eq!(m.pop_last(), Some((3, "c")));
+}
+
+#[test]
+fn try_insert() {
+    let mut m: ArrayMap<[(u8, u8); 2]> = vec![(2, 2), (1, 1)].into_iter().collect();
+    assert_eq!(m.as_slice(), &[(1, 1), (2, 2)]);
+    assert_eq!(m.try_insert(1, 10), Ok(Some(1)));
+    assert_eq!(m.try_insert(0, 0), Err(CapacityError::new((0, 0))));
+    assert_eq!(m.values().cloned().collect::<Vec<_>>(), &[10, 2]);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn entry() {
+    let mut m = SmallMap::<[(String, u32); 2]>::new();
+    for word in "a b a c a b".split(' ') {
+        *m.entry(word.into()).or_insert(0) += 1;
+    }
+    assert_eq!(m.iter().map(|(k, &v)| (&k[..], v)).collect::<Vec<_>>(), &[("a", 3), ("b", 2), ("c", 1)]);
+    assert!(m.into_inner().is_spilled());
+
+    let mut m: SmallMap<[(&str, u32); 2]> = [("a", 3), ("b", 2), ("c", 1)].iter().cloned().collect();
+    assert_eq!(m.get("a"), Some(&3));
+    m.entry("b").and_modify(|v| *v *= 10).or_default();
+    assert_eq!(m["b"], 20);
+    match m.entry("d") {
+        Entry::Vacant(e) => {
+            assert_eq!(e.key(), &"d");
+            e.insert(4);
+        },
+        Entry::Occupied(..) => unreachable!(),
+    }
+    match m.entry("a") {
+        Entry::Occupied(e) => assert_eq!(e.remove(), 3),
+        Entry::Vacant(..) => unreachable!(),
+    }
+    assert_eq!(m.into_iter().collect::<Vec<_>>(), &[("b", 20), ("c", 1), ("d", 4)]);
+}
+
+#[test]
+fn range() {
+    let mut m: ArrayMap<[(u32, u32); 8]> = (0..8).map(|i| (i * 2, i)).collect();
+    assert_eq!(m.range(3..7).map(|(&k, _)| k).collect::<Vec<_>>(), &[4, 6]);
+    assert_eq!(m.range(4..=8).map(|(&k, _)| k).collect::<Vec<_>>(), &[4, 6, 8]);
+    assert_eq!(m.range(..2).count(), 1);
+    assert_eq!(m.range(15..).count(), 0);
+    for (_, v) in m.range_mut(10..) {
+        *v = 0;
+    }
+    assert_eq!(m.values().rev().take(3).cloned().collect::<Vec<_>>(), &[0, 0, 0]);
+}
+
+#[test]
+#[should_panic]
+fn range_backwards() {
+    let m = ArrayMap::<[(u32, u32); 1]>::new();
+    #[allow(clippy::reversed_empty_ranges)]
+    m.range(2..1);
+}
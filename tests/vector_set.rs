@@ -0,0 +1,29 @@
+use stack::{ArraySet, CapacityError};
+#[cfg(feature = "alloc")]
+use stack::SmallSet;
+
+#[test]
+fn insert_remove() {
+    let mut s: ArraySet<[u32; 4]> = [3, 1, 3, 2].iter().cloned().collect();
+    assert_eq!(s.as_slice(), &[1, 2, 3]);
+    assert!(s.insert(0));
+    assert!(!s.insert(2));
+    assert_eq!(s.try_insert(4), Err(CapacityError::new(4)));
+    assert_eq!(s.try_insert(1), Ok(false));
+    assert!(s.remove(&1));
+    assert!(!s.contains(&1));
+    assert_eq!(s.range(1..).cloned().collect::<Vec<_>>(), &[2, 3]);
+    assert_eq!(s.pop_first(), Some(0));
+    assert_eq!(format!("{:?}", s), "{2, 3}");
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn spill() {
+    let mut s = SmallSet::<[String; 2]>::new();
+    s.extend(["c", "b", "a"].iter().map(|&s| s.to_owned()));
+    assert!(s.contains("a"));
+    assert_eq!(s.get("b").map(|s| &s[..]), Some("b"));
+    assert_eq!(s.take("c"), Some("c".to_owned()));
+    assert_eq!(s.into_iter().collect::<Vec<_>>(), &["a", "b"]);
+}